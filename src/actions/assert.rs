@@ -3,17 +3,22 @@ use colored::*;
 use serde_json::json;
 use serde_yaml::Value;
 
+use crate::actions::condition::Condition;
 use crate::actions::extract;
 use crate::actions::Runnable;
 use crate::benchmark::{Context, Pool, Reports};
 use crate::config::Config;
 use crate::interpolator;
+use crate::secrets;
 
-#[derive(Clone)]
 pub struct Assert {
   name: String,
-  key: String,
-  value: String,
+  assertion: Assertion,
+}
+
+enum Assertion {
+  Equals(String, String),
+  That(Condition),
 }
 
 impl Assert {
@@ -24,13 +29,17 @@ impl Assert {
   pub fn new(item: &Value, _with_item: Option<Value>) -> Assert {
     let name = extract(item, "name");
     let assert_val = item.get("assert").expect("assert field is required");
-    let key = extract(assert_val, "key");
-    let value = extract(assert_val, "value");
+
+    // Assertions are either a key and its expected value, or any expression
+    let assertion = if assert_val.get("that").is_some() {
+      Assertion::That(Condition::new(assert_val, "that"))
+    } else {
+      Assertion::Equals(extract(assert_val, "key"), extract(assert_val, "value"))
+    };
 
     Assert {
       name,
-      key,
-      value,
+      assertion,
     }
   }
 }
@@ -38,17 +47,51 @@ impl Assert {
 #[async_trait]
 impl Runnable for Assert {
   async fn execute(&self, context: &mut Context, _reports: &mut Reports, _pool: &Pool, config: &Config) {
-    if !config.quiet {
-      println!("{:width$} {}={}?", self.name.green(), self.key.cyan().bold(), self.value.magenta(), width = 25);
-    }
+    match &self.assertion {
+      Assertion::Equals(key, value) => {
+        if !config.quiet {
+          println!("{:width$} {}={}?", self.name.green(), key.cyan().bold(), value.magenta(), width = 25);
+        }
+
+        let interpolator = interpolator::Interpolator::new(context);
+        let eval = format!("{{{{ {} }}}}", key);
+        let stored = interpolator.resolve(&eval, true);
+        let assertion = json!(value.to_owned());
 
-    let interpolator = interpolator::Interpolator::new(context);
-    let eval = format!("{{{{ {} }}}}", &self.key);
-    let stored = interpolator.resolve(&eval, true);
-    let assertion = json!(self.value.to_owned());
+        if !stored.eq(&assertion) {
+          panic!("{}", secrets::redact(&format!("Assertion mismatched: {stored} != {assertion}")));
+        }
+      }
+      Assertion::That(condition) => {
+        if !config.quiet {
+          println!("{:width$} {}?", self.name.green(), condition.source.cyan().bold(), width = 25);
+        }
 
-    if !stored.eq(&assertion) {
-      panic!("Assertion mismatched: {stored} != {assertion}");
+        if !condition.is_satisfied(context, config) {
+          panic!("Assertion failed: {}", condition.source);
+        }
+      }
     }
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn parses_assertions() {
+    let docs = crate::reader::read_file_as_yml_from_str("---\nname: Check\nassert:\n  key: foo.status\n  value: 200");
+    assert!(matches!(Assert::new(&docs[0], None).assertion, Assertion::Equals(..)));
+
+    let docs = crate::reader::read_file_as_yml_from_str("---\nname: Check\nassert:\n  that: foo.status == 200 and foo.body.count > 0");
+    assert!(matches!(Assert::new(&docs[0], None).assertion, Assertion::That(..)));
+  }
+
+  #[test]
+  #[should_panic(expected = "Invalid `that` expression")]
+  fn invalid_assertion() {
+    let docs = crate::reader::read_file_as_yml_from_str("---\nname: Check\nassert:\n  that: foo.status ==");
+    Assert::new(&docs[0], None);
+  }
+}
@@ -0,0 +1,147 @@
+use std::panic::AssertUnwindSafe;
+
+use async_trait::async_trait;
+use colored::*;
+use futures::FutureExt;
+use serde_json::{json, Value as JsonValue};
+use serde_yaml::Value;
+
+use crate::actions::Runnable;
+use crate::actions::{extract, step_failed, yaml_to_json};
+use crate::benchmark::{Benchmark, Context, Pool, Reports};
+use crate::config::Config;
+use crate::interpolator;
+
+pub struct Block {
+  name: String,
+  vars: Vec<(String, JsonValue)>,
+  items: Benchmark,
+  rescue: Benchmark,
+  always: Benchmark,
+}
+
+impl Block {
+  pub fn is_that_you(item: &Value) -> bool {
+    item.get("block").and_then(|v| v.as_sequence()).is_some()
+  }
+
+  pub fn new(item: &Value, items: Benchmark, rescue: Benchmark, always: Benchmark) -> Block {
+    let name = extract(item, "name");
+
+    let vars = match item.get("vars") {
+      Some(vars) => vars
+        .as_mapping()
+        .expect("`vars` needs to be a mapping")
+        .iter()
+        .map(|(key, value)| {
+          let key = key.as_str().expect("`vars` keys must be strings");
+
+          (key.to_string(), yaml_to_json(value.clone()))
+        })
+        .collect(),
+      None => Vec::new(),
+    };
+
+    Block {
+      name,
+      vars,
+      items,
+      rescue,
+      always,
+    }
+  }
+}
+
+// Steps also fail if they panic, like failed assertions
+async fn run_steps(items: &Benchmark, context: &mut Context, reports: &mut Reports, pool: &Pool, config: &Config) -> bool {
+  for item in items.iter() {
+    let begin = reports.len();
+    let result = AssertUnwindSafe(item.execute(context, reports, pool, config)).catch_unwind().await;
+
+    if result.is_err() || step_failed(&reports[begin..]) {
+      return false;
+    }
+  }
+
+  true
+}
+
+#[async_trait]
+impl Runnable for Block {
+  async fn execute(&self, context: &mut Context, reports: &mut Reports, pool: &Pool, config: &Config) {
+    for (key, value) in self.vars.iter() {
+      let value = match value {
+        JsonValue::String(s) => json!(interpolator::Interpolator::new(context).resolve(s, !config.relaxed_interpolations)),
+        _ => value.clone(),
+      };
+
+      context.insert(key.to_owned(), value);
+    }
+
+    let succeeded = run_steps(&self.items, context, reports, pool, config).await;
+
+    if !succeeded {
+      if !config.quiet {
+        println!("{:width$} {}", self.name.green(), "failed".red().bold(), width = 25);
+      }
+
+      run_steps(&self.rescue, context, reports, pool, config).await;
+    }
+
+    run_steps(&self.always, context, reports, pool, config).await;
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::actions::Stub;
+  use crate::benchmark::PoolStore;
+  use std::sync::{Arc, Mutex};
+
+  fn stubs(stubs: &[(&'static str, u16)]) -> Benchmark {
+    stubs
+      .iter()
+      .map(|&(name, status)| {
+        Box::new(Stub {
+          name,
+          status,
+        }) as Box<dyn Runnable + Sync + Send>
+      })
+      .collect()
+  }
+
+  fn run(items: &[(&'static str, u16)], rescue: &[(&'static str, u16)], always: &[(&'static str, u16)]) -> (JsonValue, Reports) {
+    let item = &crate::reader::read_file_as_yml_from_str("---\nname: Authenticated\nblock: []")[0];
+    let block = Block::new(item, stubs(items), stubs(rescue), stubs(always));
+    let pool: Pool = Arc::new(Mutex::new(PoolStore::new()));
+    let mut context = Context::new();
+    let mut reports = Reports::new();
+
+    futures::executor::block_on(block.execute(&mut context, &mut reports, &pool, &Config::test()));
+
+    (context.get("runs").cloned().unwrap_or(json!([])), reports)
+  }
+
+  #[test]
+  fn runs_always_after_succeeded_blocks() {
+    let (runs, reports) = run(&[("login", 200), ("fetch", 200)], &[("report", 200)], &[("logout", 200)]);
+
+    assert_eq!(runs, json!(["login", "fetch", "logout"]));
+    assert_eq!(reports.len(), 3);
+  }
+
+  #[test]
+  fn runs_rescue_and_always_after_failed_blocks() {
+    let (runs, _) = run(&[("login", 500), ("fetch", 200)], &[("report", 200)], &[("logout", 200)]);
+
+    assert_eq!(runs, json!(["login", "report", "logout"]));
+  }
+
+  #[test]
+  fn runs_always_after_failed_rescues() {
+    let (runs, _) = run(&[("login", 200), ("fetch", 404)], &[("report", 500), ("notify", 200)], &[("logout", 200)]);
+
+    assert_eq!(runs, json!(["login", "fetch", "report", "logout"]));
+  }
+}
@@ -0,0 +1,45 @@
+use colored::*;
+use serde_yaml::Value;
+
+use crate::benchmark::Context;
+use crate::config::Config;
+use crate::expression::{self, Expression};
+use crate::interpolator;
+
+pub struct Condition {
+  pub source: String,
+  expression: Expression,
+}
+
+impl Condition {
+  pub fn new(item: &Value, attr: &str) -> Condition {
+    let source = match item.get(attr) {
+      Some(Value::String(s)) => s.to_owned(),
+      Some(Value::Bool(b)) => b.to_string(),
+      _ => panic!("`{attr}` needs to be an expression. Try adding quotes"),
+    };
+    let expression = Expression::parse(&source).unwrap_or_else(|e| panic!("Invalid `{attr}` expression '{source}': {e}"));
+
+    Condition {
+      source,
+      expression,
+    }
+  }
+
+  pub fn is_satisfied(&self, context: &Context, config: &Config) -> bool {
+    let interpolator = interpolator::Interpolator::new(context);
+
+    match self.expression.evaluate(&|name| interpolator.resolve_variable(name)) {
+      Ok(value) => expression::is_truthy(&value),
+      Err(e) => {
+        if !config.relaxed_interpolations {
+          panic!("Error evaluating '{}': {}", self.source, e);
+        }
+
+        eprintln!("{} Error evaluating '{}': {}", "WARNING!".yellow().bold(), self.source, e);
+
+        false
+      }
+    }
+  }
+}
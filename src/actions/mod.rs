@@ -1,22 +1,41 @@
 use async_trait::async_trait;
+use serde_json::{json, Map, Value as JsonValue};
 use serde_yaml::Value;
 
 mod assert;
 mod assign;
+mod block;
+mod condition;
 mod delay;
 mod exec;
+mod parallel;
+mod random_choice;
 mod request;
+mod retry;
+mod shared;
+mod transaction;
+mod until;
+mod when;
 
 pub use self::assert::Assert;
 pub use self::assign::Assign;
+pub use self::block::Block;
 pub use self::delay::Delay;
 pub use self::exec::Exec;
+pub use self::parallel::Parallel;
+pub use self::random_choice::RandomChoice;
 pub use self::request::Request;
+pub use self::shared::Shared;
+pub use self::transaction::Transaction;
+pub use self::until::Until;
+pub use self::when::When;
 
 use crate::benchmark::{Context, Pool, Reports};
 use crate::config::Config;
+use crate::secrets;
 
 use std::fmt;
+use std::time::Duration;
 
 #[async_trait]
 pub trait Runnable {
@@ -28,6 +47,22 @@ pub struct Report {
   pub name: String,
   pub duration: f64,
   pub status: u16,
+  pub error: Option<String>,
+  pub max_duration: Option<f64>,
+  // Set for reports timing several items at once, which aren't requests themselves
+  pub aggregate: bool,
+  // Set for attempts retried by a retry policy, which aren't the final outcome of a request
+  pub retried: bool,
+}
+
+impl Report {
+  pub fn is_successful(&self) -> bool {
+    self.status / 100 == 2 && self.error.is_none()
+  }
+
+  pub fn exceeds_max_duration(&self) -> bool {
+    self.max_duration.is_some_and(|max_duration| self.duration > max_duration)
+  }
 }
 
 impl fmt::Debug for Report {
@@ -38,7 +73,55 @@ impl fmt::Debug for Report {
 
 impl fmt::Display for Report {
   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-    write!(f, "\n- name: {}\n  duration: {}\n  status: {}\n", self.name, self.duration, self.status)
+    write!(f, "\n- name: {}\n  duration: {}\n  status: {}\n", secrets::redact(&self.name), self.duration, self.status)?;
+
+    if let Some(ref error) = self.error {
+      writeln!(f, "  error: {:?}", secrets::redact(error))?;
+    }
+
+    if self.retried {
+      writeln!(f, "  retried: true")?;
+    }
+
+    Ok(())
+  }
+}
+
+// Steps fail if their reports aren't successful. Steps reporting their own
+// outcome, like loops, are decided by their last aggregated report and the reports
+// after it. Retried attempts don't count, only the final outcome of their requests
+pub fn step_failed(reports: &[Report]) -> bool {
+  let failed = |reports: &[Report]| reports.iter().any(|r| !r.aggregate && !r.retried && !r.is_successful());
+
+  match reports.iter().rposition(|r| r.aggregate) {
+    Some(index) => !reports[index].is_successful() || failed(&reports[index + 1..]),
+    None => failed(reports),
+  }
+}
+
+// Item reporting a fixed status, which records its runs in the `runs` variable
+#[cfg(test)]
+pub struct Stub {
+  pub name: &'static str,
+  pub status: u16,
+}
+
+#[cfg(test)]
+#[async_trait]
+impl Runnable for Stub {
+  async fn execute(&self, context: &mut Context, reports: &mut Reports, _pool: &Pool, _config: &Config) {
+    let runs = context.entry("runs").or_insert_with(|| json!([]));
+    runs.as_array_mut().unwrap().push(json!(self.name));
+
+    reports.push(Report {
+      name: self.name.to_string(),
+      duration: 1.0,
+      status: self.status,
+      error: None,
+      max_duration: None,
+      aggregate: false,
+      retried: false,
+    });
   }
 }
 
@@ -52,6 +135,32 @@ pub fn extract_optional<'a>(item: &'a Value, attr: &'a str) -> Option<String> {
   }
 }
 
+pub fn extract_duration<'a>(item: &'a Value, attr: &'a str) -> Option<Duration> {
+  match item.get(attr) {
+    Some(Value::Number(n)) => Some(Duration::from_millis(n.as_u64().unwrap_or_else(|| panic!("`{attr}` needs to be a positive number of milliseconds")))),
+    Some(Value::String(s)) => Some(parse_duration(s).unwrap_or_else(|| panic!("Invalid `{attr}` duration: {s}"))),
+    Some(_) => panic!("`{attr}` needs to be a duration like 250ms or 2s"),
+    None => None,
+  }
+}
+
+// Parses durations like `250ms`, `1.5s` or `2m`. Plain numbers are milliseconds
+fn parse_duration(value: &str) -> Option<Duration> {
+  let value = value.trim();
+  let split = value.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(value.len());
+  let (amount, unit) = value.split_at(split);
+  let amount: f64 = amount.parse().ok()?;
+
+  let seconds = match unit.trim() {
+    "" | "ms" => amount / 1_000.0,
+    "s" => amount,
+    "m" => amount * 60.0,
+    _ => return None,
+  };
+
+  Some(Duration::from_secs_f64(seconds))
+}
+
 pub fn extract<'a>(item: &'a Value, attr: &'a str) -> String {
   if let Some(s) = item.get(attr).and_then(|v| v.as_i64()) {
     s.to_string()
@@ -63,3 +172,106 @@ pub fn extract<'a>(item: &'a Value, attr: &'a str) -> String {
     panic!("Unknown node `{}` => {:?}", attr, item.get(attr));
   }
 }
+
+pub fn yaml_to_json(data: Value) -> JsonValue {
+  match data {
+    Value::Bool(b) => json!(b),
+    Value::Number(n) => {
+      if let Some(i) = n.as_i64() {
+        json!(i)
+      } else if let Some(f) = n.as_f64() {
+        json!(f)
+      } else {
+        // Fallback: convert to string representation
+        json!(n.to_string())
+      }
+    }
+    Value::String(s) => json!(s),
+    Value::Mapping(m) => {
+      let mut map = Map::new();
+      for (key, value) in m.iter() {
+        if let Some(key_str) = key.as_str() {
+          map.insert(key_str.to_string(), yaml_to_json(value.clone()));
+        }
+      }
+      json!(map)
+    }
+    Value::Sequence(v) => {
+      let mut array = Vec::new();
+      for value in v.iter() {
+        array.push(yaml_to_json(value.clone()));
+      }
+      json!(array)
+    }
+    Value::Null => json!(null),
+    _ => panic!("Unknown Yaml node"),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn parses_durations() {
+    assert_eq!(parse_duration("250ms"), Some(Duration::from_millis(250)));
+    assert_eq!(parse_duration("250"), Some(Duration::from_millis(250)));
+    assert_eq!(parse_duration("1.5s"), Some(Duration::from_millis(1500)));
+    assert_eq!(parse_duration("2m"), Some(Duration::from_secs(120)));
+    assert_eq!(parse_duration("2 s"), Some(Duration::from_secs(2)));
+    assert_eq!(parse_duration("2h"), None);
+    assert_eq!(parse_duration("fast"), None);
+  }
+
+  #[test]
+  fn extracts_durations() {
+    let item = &crate::reader::read_file_as_yml_from_str("---\nname: foo\nmax_duration: 250ms\ntimeout: 100")[0];
+
+    assert_eq!(extract_duration(item, "max_duration"), Some(Duration::from_millis(250)));
+    assert_eq!(extract_duration(item, "timeout"), Some(Duration::from_millis(100)));
+    assert_eq!(extract_duration(item, "missing"), None);
+  }
+
+  fn report(status: u16, aggregate: bool, retried: bool) -> Report {
+    Report {
+      name: "foo".to_string(),
+      duration: 1.0,
+      status,
+      error: None,
+      max_duration: None,
+      aggregate,
+      retried,
+    }
+  }
+
+  #[test]
+  fn steps_failed() {
+    assert!(!step_failed(&[report(200, false, false), report(500, false, true), report(200, false, false)]));
+    assert!(step_failed(&[report(200, false, false), report(500, false, false)]));
+    // Loops which eventually succeed aren't failures, unless a later report fails
+    assert!(!step_failed(&[report(500, false, false), report(200, true, false)]));
+    assert!(step_failed(&[report(200, false, false), report(520, true, false)]));
+    assert!(step_failed(&[report(200, true, false), report(500, false, false)]));
+    assert!(!step_failed(&[report(200, true, false), report(500, false, true), report(200, false, false)]));
+  }
+
+  #[test]
+  fn exceeds_max_duration() {
+    let report = Report {
+      name: "foo".to_string(),
+      duration: 300.0,
+      status: 200,
+      error: None,
+      max_duration: Some(250.0),
+      aggregate: false,
+      retried: false,
+    };
+
+    assert!(report.exceeds_max_duration());
+    assert!(!Report {
+      max_duration: None,
+      ..report.clone()
+    }
+    .exceeds_max_duration());
+  }
+}
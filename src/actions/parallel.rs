@@ -0,0 +1,152 @@
+use async_trait::async_trait;
+use colored::*;
+use futures::future::join_all;
+use serde_json::json;
+use serde_yaml::Value;
+
+use crate::actions::{extract, step_failed};
+use crate::actions::{Report, Runnable};
+use crate::benchmark::{Benchmark, Context, Pool, Reports};
+use crate::config::Config;
+
+use std::time::Instant;
+
+pub struct Parallel {
+  name: String,
+  items: Benchmark,
+}
+
+impl Parallel {
+  pub fn is_that_you(item: &Value) -> bool {
+    item.get("parallel").and_then(|v| v.as_sequence()).is_some()
+  }
+
+  pub fn new(item: &Value, items: Benchmark) -> Parallel {
+    let name = extract(item, "name");
+
+    Parallel {
+      name,
+      items,
+    }
+  }
+}
+
+// Keeps the cookies and the variables assigned or changed by a child
+fn merge(context: &mut Context, original: &Context, child: Context) {
+  for (key, value) in child {
+    if key == "cookies" {
+      if let Some(cookies) = value.as_object() {
+        let merged = context.entry("cookies").or_insert_with(|| json!({})).as_object_mut().unwrap();
+        merged.extend(cookies.clone());
+      }
+    } else if original.get(&key) != Some(&value) {
+      context.insert(key, value);
+    }
+  }
+}
+
+#[async_trait]
+impl Runnable for Parallel {
+  async fn execute(&self, context: &mut Context, reports: &mut Reports, pool: &Pool, config: &Config) {
+    let begin = Instant::now();
+
+    let mut contexts: Vec<Context> = self.items.iter().map(|_| context.clone()).collect();
+    let mut children_reports: Vec<Reports> = self.items.iter().map(|_| Reports::new()).collect();
+
+    let children = self.items.iter().zip(contexts.iter_mut()).zip(children_reports.iter_mut()).map(|((item, context), reports)| item.execute(context, reports, pool, config));
+    join_all(children).await;
+
+    let duration_ms = begin.elapsed().as_secs_f64() * 1000.0;
+    let failed = children_reports.iter().any(|reports| step_failed(reports));
+
+    let original = context.clone();
+    for child in contexts {
+      merge(context, &original, child);
+    }
+
+    reports.extend(children_reports.into_iter().flatten());
+
+    if !config.quiet {
+      let status_text = if failed {
+        "failed".red()
+      } else {
+        "completed".yellow()
+      };
+
+      println!("{:width$} {} {}", self.name.green(), status_text, format!("{}ms", duration_ms.round()).cyan(), width = 25);
+    }
+
+    reports.push(Report {
+      name: format!("{} (parallel)", self.name),
+      duration: duration_ms,
+      status: if failed {
+        520
+      } else {
+        200
+      },
+      error: None,
+      max_duration: None,
+      aggregate: true,
+      retried: false,
+    });
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::actions::Stub;
+  use crate::benchmark::PoolStore;
+  use std::sync::{Arc, Mutex};
+
+  #[test]
+  fn reports_children_and_the_whole_group() {
+    let item = &crate::reader::read_file_as_yml_from_str("---\nname: Load dashboard\nparallel: []")[0];
+    let items: Benchmark = vec![
+      Box::new(Stub {
+        name: "Fetch user",
+        status: 200,
+      }),
+      Box::new(Stub {
+        name: "Fetch orders",
+        status: 500,
+      }),
+    ];
+    let parallel = Parallel::new(item, items);
+    let pool: Pool = Arc::new(Mutex::new(PoolStore::new()));
+    let mut reports = Reports::new();
+
+    futures::executor::block_on(parallel.execute(&mut Context::new(), &mut reports, &pool, &Config::test()));
+
+    let names: Vec<&str> = reports.iter().map(|r| r.name.as_str()).collect();
+    assert_eq!(names, vec!["Fetch user", "Fetch orders", "Load dashboard (parallel)"]);
+    assert!(reports[2].aggregate);
+    assert!(!reports[2].is_successful());
+  }
+
+  #[test]
+  fn merges_children_contexts() {
+    let mut context = Context::new();
+    context.insert("iteration".to_string(), json!("1"));
+    context.insert("token".to_string(), json!("old"));
+    context.insert("cookies".to_string(), json!({"session": "a"}));
+
+    let original = context.clone();
+
+    let mut first = original.clone();
+    first.insert("token".to_string(), json!("new"));
+    first.insert("cookies".to_string(), json!({"session": "a", "cart": "1"}));
+
+    let mut second = original.clone();
+    second.insert("user".to_string(), json!({"id": 1}));
+    second.insert("cookies".to_string(), json!({"session": "a", "theme": "dark"}));
+
+    merge(&mut context, &original, first);
+    merge(&mut context, &original, second);
+
+    assert_eq!(context.get("iteration"), Some(&json!("1")));
+    assert_eq!(context.get("token"), Some(&json!("new")));
+    assert_eq!(context.get("user"), Some(&json!({"id": 1})));
+    assert_eq!(context.get("cookies"), Some(&json!({"session": "a", "cart": "1", "theme": "dark"})));
+  }
+}
@@ -0,0 +1,47 @@
+use async_trait::async_trait;
+use colored::*;
+use rand::distributions::{Distribution, WeightedIndex};
+use serde_yaml::Value;
+
+use crate::actions::extract_optional;
+use crate::actions::Runnable;
+use crate::benchmark::{Benchmark, Context, Pool, Reports};
+use crate::config::Config;
+
+pub struct RandomChoice {
+  name: String,
+  weights: WeightedIndex<u64>,
+  branches: Vec<Benchmark>,
+}
+
+impl RandomChoice {
+  pub fn is_that_you(item: &Value) -> bool {
+    item.get("random_choice").and_then(|v| v.as_sequence()).is_some()
+  }
+
+  pub fn new(item: &Value, weights: Vec<u64>, branches: Vec<Benchmark>) -> RandomChoice {
+    let name = extract_optional(item, "name").unwrap_or_default();
+    let weights = WeightedIndex::new(weights).expect("`random_choice` weights can't all be zero");
+
+    RandomChoice {
+      name,
+      weights,
+      branches,
+    }
+  }
+}
+
+#[async_trait]
+impl Runnable for RandomChoice {
+  async fn execute(&self, context: &mut Context, reports: &mut Reports, pool: &Pool, config: &Config) {
+    let index = self.weights.sample(&mut rand::thread_rng());
+
+    if !config.quiet {
+      println!("{:width$} {} {}", self.name.green(), "chose branch".yellow(), (index + 1).to_string().cyan().bold(), width = 25);
+    }
+
+    for item in self.branches[index].iter() {
+      item.execute(context, reports, pool, config).await;
+    }
+  }
+}
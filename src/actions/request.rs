@@ -11,15 +11,20 @@ use serde_yaml::Value as YamlValue;
 use std::fmt::Write;
 use std::fs::File;
 use std::io::Read;
+use tokio::time::sleep;
 use url::Url;
 
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Map, Value};
 
-use crate::actions::{extract, extract_optional};
+use crate::actions::retry::Retry;
+use crate::actions::{extract, extract_duration, extract_optional, yaml_to_json};
 use crate::benchmark::{Context, Pool, Reports};
 use crate::config::Config;
+use crate::expectation::Expectation;
+use crate::extractor::{self, Extractor};
 use crate::interpolator;
+use crate::secrets;
 
 use crate::actions::{Report, Runnable};
 
@@ -29,6 +34,7 @@ static USER_AGENT: &str = "drill";
 pub enum Body {
   Template(String),
   Binary(Vec<u8>),
+  Json(Value),
 }
 
 #[derive(Clone)]
@@ -43,6 +49,10 @@ pub struct Request {
   pub with_item: Option<YamlValue>,
   pub index: Option<u32>,
   pub assign: Option<String>,
+  pub extract: Vec<Extractor>,
+  pub expectation: Option<Expectation>,
+  pub max_duration: Option<f64>,
+  pub retry: Option<Retry>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -57,11 +67,15 @@ impl Request {
     item.get("request").and_then(|v| v.as_mapping()).is_some()
   }
 
-  pub fn new(item: &YamlValue, with_item: Option<YamlValue>, index: Option<u32>) -> Request {
+  pub fn new(parent_path: &str, item: &YamlValue, with_item: Option<YamlValue>, index: Option<u32>) -> Request {
     let name = extract(item, "name");
     let request_val = item.get("request").expect("request field is required");
     let url = extract(request_val, "url");
     let assign = extract_optional(item, "assign");
+    let extract = extractor::extract_extractors(item);
+    let expectation = Expectation::new(parent_path, item);
+    let max_duration = extract_duration(item, "max_duration").map(|d| d.as_secs_f64() * 1_000.0);
+    let retry = Retry::new(item);
 
     let method = if let Some(v) = extract_optional(request_val, "method") {
       v.to_uppercase()
@@ -80,8 +94,10 @@ impl Request {
         Some(Body::Binary(buffer))
       } else if let Some(hex_str) = request_val.get("body").and_then(|v| v.get("hex")).and_then(|v| v.as_str()) {
         Some(Body::Binary(hex::decode(hex_str).expect("Invalid hex string")))
+      } else if let Some(json) = request_val.get("body").and_then(|v| v.get("json")) {
+        Some(Body::Json(yaml_to_json(json.clone())))
       } else {
-        panic!("{} Body must be string, file or hex!!", "WARNING!".yellow().bold());
+        panic!("{} Body must be string, file, hex or json!!", "WARNING!".yellow().bold());
       }
     } else {
       None
@@ -113,6 +129,10 @@ impl Request {
       with_item,
       index,
       assign,
+      extract,
+      expectation,
+      max_duration,
+      retry,
     }
   }
 
@@ -124,7 +144,7 @@ impl Request {
     }
   }
 
-  async fn send_request(&self, context: &mut Context, pool: &Pool, config: &Config) -> (Option<Response>, f64) {
+  async fn send_request(&self, context: &mut Context, pool: &Pool, config: &Config) -> (Result<Response, reqwest::Error>, f64) {
     let mut uninterpolator = None;
 
     // Resolve the name
@@ -162,8 +182,6 @@ impl Request {
     let url = Url::parse(&interpolated_base_url).expect("Invalid url!");
     let domain = format!("{}://{}:{}", url.scheme(), url.host_str().unwrap(), url.port().unwrap_or(0)); // Unique domain key for keep-alive
 
-    let interpolated_body;
-
     // Method
     let method = match self.method.to_uppercase().as_ref() {
       "GET" => Method::GET,
@@ -175,17 +193,24 @@ impl Request {
       _ => panic!("Unknown method '{}'", self.method),
     };
 
-    // Resolve the body
+    // Resolve the body, before locking the pool
+    let body: Option<reqwest::Body> = match self.body.as_ref() {
+      Some(Body::Template(template_body)) => Some(uninterpolator.get_or_insert(interpolator::Interpolator::new(context)).resolve(template_body, !config.relaxed_interpolations).into()),
+      Some(Body::Binary(binary_body)) => Some(binary_body.clone().into()),
+      Some(Body::Json(json_body)) => {
+        let interpolator = uninterpolator.get_or_insert(interpolator::Interpolator::new(context));
+
+        Some(resolve_json(interpolator, json_body, !config.relaxed_interpolations).to_string().into())
+      }
+      None => None,
+    };
+
     let (client, request) = {
       let mut pool2 = pool.lock().unwrap();
       let client = pool2.entry(domain).or_insert_with(|| ClientBuilder::default().danger_accept_invalid_certs(config.no_check_certificate).build().unwrap());
 
-      let request = match self.body.as_ref() {
-        Some(Body::Template(template_body)) => {
-          interpolated_body = uninterpolator.get_or_insert(interpolator::Interpolator::new(context)).resolve(template_body, !config.relaxed_interpolations);
-          client.request(method, interpolated_base_url.as_str()).body(interpolated_body)
-        }
-        Some(Body::Binary(binary_body)) => client.request(method, interpolated_base_url.as_str()).body(binary_body.clone()),
+      let request = match body {
+        Some(body) => client.request(method, interpolated_base_url.as_str()).body(body),
         None => client.request(method, interpolated_base_url.as_str()),
       };
 
@@ -203,8 +228,12 @@ impl Request {
       headers.insert(header::COOKIE, HeaderValue::from_str(&cookie).unwrap());
     }
 
-    // Resolve headers
-    for (key, val) in self.headers.iter() {
+    if let Some(Body::Json(_)) = self.body {
+      headers.insert(header::CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    }
+
+    // Resolve headers. The ones of the request win over the ones of the environment
+    for (key, val) in config.headers.iter().filter(|(key, _)| !self.headers.keys().any(|k| k.eq_ignore_ascii_case(key))).chain(self.headers.iter()) {
       let interpolated_header = uninterpolator.get_or_insert(interpolator::Interpolator::new(context)).resolve(val, !config.relaxed_interpolations);
       headers.insert(HeaderName::from_bytes(key.as_bytes()).unwrap(), HeaderValue::from_str(&interpolated_header).unwrap());
     }
@@ -223,9 +252,9 @@ impl Request {
     match response_result {
       Err(e) => {
         if !config.quiet || config.verbose {
-          println!("Error connecting '{}': {:?}", interpolated_base_url.as_str(), e);
+          println!("{}", secrets::redact(&format!("Error connecting '{}': {:?}", interpolated_base_url.as_str(), e)));
         }
-        (None, duration_ms)
+        (Err(e), duration_ms)
       }
       Ok(response) => {
         if !config.quiet {
@@ -238,47 +267,18 @@ impl Request {
             status.to_string().yellow()
           };
 
-          println!("{:width$} {} {} {}", interpolated_name.green(), interpolated_base_url.blue().bold(), status_text, Request::format_time(duration_ms, config.nanosec).cyan(), width = 25);
-        }
-
-        (Some(response), duration_ms)
-      }
-    }
-  }
-}
+          let time_text = if self.max_duration.is_some_and(|max_duration| duration_ms > max_duration) {
+            Request::format_time(duration_ms, config.nanosec).red()
+          } else {
+            Request::format_time(duration_ms, config.nanosec).cyan()
+          };
 
-fn yaml_to_json(data: YamlValue) -> Value {
-  match data {
-    YamlValue::Bool(b) => json!(b),
-    YamlValue::Number(n) => {
-      if let Some(i) = n.as_i64() {
-        json!(i)
-      } else if let Some(f) = n.as_f64() {
-        json!(f)
-      } else {
-        // Fallback: convert to string representation
-        json!(n.to_string())
-      }
-    }
-    YamlValue::String(s) => json!(s),
-    YamlValue::Mapping(m) => {
-      let mut map = Map::new();
-      for (key, value) in m.iter() {
-        if let Some(key_str) = key.as_str() {
-          map.insert(key_str.to_string(), yaml_to_json(value.clone()));
+          println!("{:width$} {} {} {}", secrets::redact(&interpolated_name).green(), secrets::redact(&interpolated_base_url).blue().bold(), status_text, time_text, width = 25);
         }
+
+        (Ok(response), duration_ms)
       }
-      json!(map)
-    }
-    YamlValue::Sequence(v) => {
-      let mut array = Vec::new();
-      for value in v.iter() {
-        array.push(yaml_to_json(value.clone()));
-      }
-      json!(array)
     }
-    YamlValue::Null => json!(null),
-    _ => panic!("Unknown Yaml node"),
   }
 }
 
@@ -289,64 +289,115 @@ impl Runnable for Request {
       context.insert("item".to_string(), yaml_to_json(self.with_item.clone().unwrap()));
     }
 
-    if self.index.is_some() {
-      context.insert("index".to_string(), json!(self.index.unwrap()));
+    if let Some(index) = self.index {
+      context.insert("index".to_string(), json!(index));
     }
 
-    let (res, duration_ms) = self.send_request(context, pool, config).await;
+    let mut attempt = 1;
+
+    let (res, duration_ms) = loop {
+      let (res, duration_ms) = self.send_request(context, pool, config).await;
+
+      match self.retry {
+        Some(ref retry) if attempt < retry.attempts && retry.is_retryable(&res) => {
+          if config.verbose {
+            log_response(log_message_response(res.as_ref().ok(), duration_ms), &None);
+          }
+
+          reports.push(Report {
+            name: self.name.to_owned(),
+            duration: duration_ms,
+            status: res.map_or(520u16, |response| response.status().as_u16()),
+            error: None,
+            max_duration: self.max_duration,
+            aggregate: false,
+            retried: true,
+          });
+
+          if !config.quiet {
+            println!("{:width$} {} {}/{}", self.name.green(), "retrying".yellow(), attempt.to_string().cyan().bold(), (retry.attempts - 1).to_string().cyan(), width = 25);
+          }
+
+          sleep(retry.delay(attempt)).await;
+          attempt += 1;
+        }
+        _ => break (res, duration_ms),
+      }
+    };
 
     let log_message_response = if config.verbose {
-      Some(log_message_response(&res, duration_ms))
+      Some(log_message_response(res.as_ref().ok(), duration_ms))
     } else {
       None
     };
 
     match res {
-      None => reports.push(Report {
+      Err(_) => reports.push(Report {
         name: self.name.to_owned(),
         duration: duration_ms,
         status: 520u16,
+        error: None,
+        max_duration: self.max_duration,
+        aggregate: false,
+        retried: false,
       }),
-      Some(response) => {
+      Ok(response) => {
         let status = response.status().as_u16();
 
-        reports.push(Report {
-          name: self.name.to_owned(),
-          duration: duration_ms,
-          status,
-        });
-
         for cookie in response.cookies() {
           let cookies = context.entry("cookies").or_insert_with(|| json!({})).as_object_mut().unwrap();
           cookies.insert(cookie.name().to_string(), json!(cookie.value().to_string()));
         }
 
-        let data = if let Some(ref key) = self.assign {
-          let mut headers = Map::new();
-
-          response.headers().iter().for_each(|(header, value)| {
-            headers.insert(header.to_string(), json!(value.to_str().unwrap()));
-          });
+        let data = if self.assign.is_some() || !self.extract.is_empty() || self.expectation.is_some() {
+          let headers = response_headers(response.headers());
 
           let data = response.text().await.unwrap();
 
           let body: Value = serde_json::from_str(&data).unwrap_or(serde_json::Value::Null);
 
-          let assigned = AssignedRequest {
-            status,
-            body,
-            headers,
-          };
+          for extractor in self.extract.iter() {
+            context.insert(extractor.key.to_owned(), extractor.extract(&data, &body, &headers));
+          }
+
+          if let Some(ref key) = self.assign {
+            let assigned = AssignedRequest {
+              status,
+              body,
+              headers,
+            };
 
-          let value = serde_json::to_value(assigned).unwrap();
+            let value = serde_json::to_value(assigned).unwrap();
 
-          context.insert(key.to_owned(), value);
+            context.insert(key.to_owned(), value);
+          }
 
           Some(data)
         } else {
           None
         };
 
+        let error = match (&self.expectation, &data) {
+          (Some(expectation), Some(data)) => expectation.validate(data).map(|error| secrets::redact(&error)),
+          _ => None,
+        };
+
+        if let Some(ref error) = error {
+          if !config.quiet {
+            println!("{:width$} {} {}", self.name.green(), "Validation failed".red().bold(), error, width = 25);
+          }
+        }
+
+        reports.push(Report {
+          name: self.name.to_owned(),
+          duration: duration_ms,
+          status,
+          error,
+          max_duration: self.max_duration,
+          aggregate: false,
+          retried: false,
+        });
+
         if let Some(msg) = log_message_response {
           log_response(msg, &data)
         }
@@ -361,10 +412,10 @@ fn log_request(request: &reqwest::Request) {
   write!(message, " {} {},", "URL:".bold(), request.url()).unwrap();
   write!(message, " {} {},", "METHOD:".bold(), request.method()).unwrap();
   write!(message, " {} {:?}", "HEADERS:".bold(), request.headers()).unwrap();
-  println!("{message}");
+  println!("{}", secrets::redact(&message));
 }
 
-fn log_message_response(response: &Option<reqwest::Response>, duration_ms: f64) -> String {
+fn log_message_response(response: Option<&reqwest::Response>, duration_ms: f64) -> String {
   let mut message = String::new();
   match response {
     Some(response) => {
@@ -380,13 +431,29 @@ fn log_message_response(response: &Option<reqwest::Response>, duration_ms: f64)
   message
 }
 
+// Header values aren't always visible ASCII, so they're read lossily
+fn response_headers(headers: &HeaderMap) -> Map<String, Value> {
+  headers.iter().map(|(header, value)| (header.to_string(), json!(String::from_utf8_lossy(value.as_bytes())))).collect()
+}
+
+// Interpolates every key and string of a json body. Strings made of a single interpolation
+// keep the type of their value, so `{{ user.id }}` is sent as a number
+fn resolve_json(interpolator: &interpolator::Interpolator, value: &Value, strict: bool) -> Value {
+  match value {
+    Value::String(template) => interpolator.resolve_value(template, strict),
+    Value::Array(items) => Value::Array(items.iter().map(|item| resolve_json(interpolator, item, strict)).collect()),
+    Value::Object(map) => Value::Object(map.iter().map(|(key, item)| (interpolator.resolve(key, strict), resolve_json(interpolator, item, strict))).collect()),
+    _ => value.clone(),
+  }
+}
+
 fn log_response(log_message_response: String, body: &Option<String>) {
   let mut message = String::new();
   write!(message, "{}{}", "<<<".bold().green(), log_message_response).unwrap();
   if let Some(body) = body.as_ref() {
     write!(message, " {} {:?}", "BODY:".bold(), body).unwrap()
   }
-  println!("{message}");
+  println!("{}", secrets::redact(&message));
 }
 
 #[cfg(test)]
@@ -443,7 +510,7 @@ request:
   #[test]
   fn test_body_template_string() {
     let yaml = create_yaml_request_with_string_body("Hello, World!");
-    let request = Request::new(&yaml, None, None);
+    let request = Request::new("example/benchmark.yml", &yaml, None, None);
 
     match request.body {
       Some(Body::Template(content)) => {
@@ -457,7 +524,7 @@ request:
   fn test_body_hex() {
     // "Hello" in hex is "48656c6c6f"
     let yaml = create_yaml_request_with_hex_body("48656c6c6f");
-    let request = Request::new(&yaml, None, None);
+    let request = Request::new("example/benchmark.yml", &yaml, None, None);
 
     match request.body {
       Some(Body::Binary(data)) => {
@@ -470,7 +537,7 @@ request:
   #[test]
   fn test_body_hex_empty() {
     let yaml = create_yaml_request_with_hex_body("");
-    let request = Request::new(&yaml, None, None);
+    let request = Request::new("example/benchmark.yml", &yaml, None, None);
 
     match request.body {
       Some(Body::Binary(data)) => {
@@ -484,7 +551,7 @@ request:
   fn test_body_hex_complex() {
     // "Hello, World!" in hex
     let yaml = create_yaml_request_with_hex_body("48656c6c6f2c20576f726c6421");
-    let request = Request::new(&yaml, None, None);
+    let request = Request::new("example/benchmark.yml", &yaml, None, None);
 
     match request.body {
       Some(Body::Binary(data)) => {
@@ -505,7 +572,7 @@ request:
     let file_path = temp_file.path().to_str().unwrap();
     let yaml = create_yaml_request_with_file_body(file_path);
 
-    let request = Request::new(&yaml, None, None);
+    let request = Request::new("example/benchmark.yml", &yaml, None, None);
 
     match request.body {
       Some(Body::Binary(data)) => {
@@ -523,7 +590,7 @@ request:
 
     let yaml = create_yaml_request_with_file_body(file_path);
 
-    let request = Request::new(&yaml, None, None);
+    let request = Request::new("example/benchmark.yml", &yaml, None, None);
 
     match request.body {
       Some(Body::Binary(data)) => {
@@ -544,7 +611,7 @@ request:
     let file_path = temp_file.path().to_str().unwrap();
     let yaml = create_yaml_request_with_file_body(file_path);
 
-    let request = Request::new(&yaml, None, None);
+    let request = Request::new("example/benchmark.yml", &yaml, None, None);
 
     match request.body {
       Some(Body::Binary(data)) => {
@@ -565,7 +632,7 @@ request:
     let file_path = temp_file.path().to_str().unwrap();
     let yaml = create_yaml_request_with_file_body(file_path);
 
-    let request = Request::new(&yaml, None, None);
+    let request = Request::new("example/benchmark.yml", &yaml, None, None);
 
     match request.body {
       Some(Body::Binary(data)) => {
@@ -585,7 +652,7 @@ request:
   method: GET
 "#;
     let yaml: YamlValue = serde_yaml::from_str(yaml_str).unwrap();
-    let request = Request::new(&yaml, None, None);
+    let request = Request::new("example/benchmark.yml", &yaml, None, None);
 
     assert!(request.body.is_none());
   }
@@ -599,16 +666,53 @@ request:
   method: DELETE
 "#;
     let yaml: YamlValue = serde_yaml::from_str(yaml_str).unwrap();
-    let request = Request::new(&yaml, None, None);
+    let request = Request::new("example/benchmark.yml", &yaml, None, None);
 
     assert!(request.body.is_none());
   }
 
+  #[test]
+  fn test_body_json() {
+    let text = "---\nname: test_request\nrequest:\n  url: http://example.com\n  method: POST\n  body:\n    json:\n      id: '{{ user.id }}'\n      tags: [a, b]\n      active: true";
+    let yaml = &crate::reader::read_file_as_yml_from_str(text)[0];
+    let request = Request::new("example/benchmark.yml", yaml, None, None);
+
+    match request.body {
+      Some(Body::Json(data)) => {
+        assert_eq!(data, json!({"id": "{{ user.id }}", "tags": ["a", "b"], "active": true}));
+      }
+      _ => panic!("Expected Body::Json"),
+    }
+  }
+
+  #[test]
+  fn test_resolve_json_keeps_types() {
+    let mut context: Context = Context::new();
+    context.insert(String::from("user"), json!({"id": 7, "name": "alice", "roles": ["admin"]}));
+
+    let interpolator = interpolator::Interpolator::new(&context);
+    let body = json!({"id": "{{ user.id }}", "{{ user.name }}": "name: {{ user.name }}", "roles": "{{ user.roles }}", "nested": [{"count": 1}]});
+
+    assert_eq!(resolve_json(&interpolator, &body, true), json!({"id": 7, "alice": "name: alice", "roles": ["admin"], "nested": [{"count": 1}]}));
+  }
+
+  #[test]
+  fn test_response_headers_with_non_ascii_values() {
+    let mut headers = HeaderMap::new();
+    headers.insert(header::CONTENT_TYPE, HeaderValue::from_static("text/plain"));
+    headers.insert("x-name", HeaderValue::from_bytes(b"caf\xe9").unwrap());
+
+    let headers = response_headers(&headers);
+
+    assert_eq!(headers.get("content-type"), Some(&json!("text/plain")));
+    assert_eq!(headers.get("x-name"), Some(&json!("caf\u{fffd}")));
+  }
+
   #[test]
   fn test_body_hex_uppercase() {
     // Test that hex decoding works with uppercase letters
     let yaml = create_yaml_request_with_hex_body("48656C6C6F");
-    let request = Request::new(&yaml, None, None);
+    let request = Request::new("example/benchmark.yml", &yaml, None, None);
 
     match request.body {
       Some(Body::Binary(data)) => {
@@ -622,7 +726,7 @@ request:
   fn test_body_hex_mixed_case() {
     // Test that hex decoding works with mixed case
     let yaml = create_yaml_request_with_hex_body("48656c6C6F");
-    let request = Request::new(&yaml, None, None);
+    let request = Request::new("example/benchmark.yml", &yaml, None, None);
 
     match request.body {
       Some(Body::Binary(data)) => {
@@ -636,21 +740,21 @@ request:
   #[should_panic(expected = "Invalid hex string")]
   fn test_body_hex_invalid() {
     let yaml = create_yaml_request_with_hex_body("InvalidHexString!");
-    Request::new(&yaml, None, None);
+    Request::new("example/benchmark.yml", &yaml, None, None);
   }
 
   #[test]
   #[should_panic(expected = "Unable to open file")]
   fn test_body_file_not_found() {
     let yaml = create_yaml_request_with_file_body("/nonexistent/path/to/file.txt");
-    Request::new(&yaml, None, None);
+    Request::new("example/benchmark.yml", &yaml, None, None);
   }
 
   #[test]
   fn test_body_priority_string_over_hex() {
     // When body is a string, it should be treated as Template, not hex
     let yaml = create_yaml_request_with_string_body("48656c6c6f");
-    let request = Request::new(&yaml, None, None);
+    let request = Request::new("example/benchmark.yml", &yaml, None, None);
 
     match request.body {
       Some(Body::Template(content)) => {
@@ -670,7 +774,7 @@ request:
   body: "PUT body content"
 "#;
     let yaml: YamlValue = serde_yaml::from_str(yaml_str).unwrap();
-    let request = Request::new(&yaml, None, None);
+    let request = Request::new("example/benchmark.yml", &yaml, None, None);
 
     match request.body {
       Some(Body::Template(content)) => {
@@ -691,7 +795,7 @@ request:
     hex: "5061746368"
 "#;
     let yaml: YamlValue = serde_yaml::from_str(yaml_str).unwrap();
-    let request = Request::new(&yaml, None, None);
+    let request = Request::new("example/benchmark.yml", &yaml, None, None);
 
     match request.body {
       Some(Body::Binary(data)) => {
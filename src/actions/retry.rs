@@ -0,0 +1,150 @@
+use serde_yaml::Value;
+
+use crate::actions::extract_duration;
+
+use std::time::Duration;
+
+const NATTEMPTS: u64 = 3;
+const NDELAY: Duration = Duration::from_millis(100);
+
+#[derive(Clone, Debug, PartialEq)]
+enum Backoff {
+  Fixed,
+  Linear,
+  Exponential,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum ErrorKind {
+  Timeout,
+  Connect,
+}
+
+#[derive(Clone, Debug)]
+pub struct Retry {
+  pub attempts: u64,
+  backoff: Backoff,
+  delay: Duration,
+  statuses: Vec<u16>,
+  errors: Vec<ErrorKind>,
+}
+
+impl Retry {
+  pub fn new(item: &Value) -> Option<Retry> {
+    let retry = item.get("retry")?;
+
+    let attempts = match retry.get("attempts") {
+      Some(attempts) => attempts.as_u64().filter(|attempts| *attempts > 0).expect("`attempts` needs to be a positive number"),
+      None => NATTEMPTS,
+    };
+
+    let backoff = match retry.get("backoff").map(|v| v.as_str().expect("`backoff` needs to be a string")) {
+      None | Some("fixed") => Backoff::Fixed,
+      Some("linear") => Backoff::Linear,
+      Some("exponential") => Backoff::Exponential,
+      Some(backoff) => panic!("Unknown `{backoff}` backoff! Use fixed, linear or exponential"),
+    };
+
+    // By default, server errors are retried, but not client errors
+    let statuses = match retry.get("statuses").and_then(|v| v.as_sequence()) {
+      Some(statuses) => statuses.iter().map(|status| status.as_u64().and_then(|s| u16::try_from(s).ok()).expect("`statuses` need to be status codes")).collect(),
+      None => vec![500, 502, 503, 504],
+    };
+
+    let errors = match retry.get("errors").and_then(|v| v.as_sequence()) {
+      Some(errors) => errors
+        .iter()
+        .map(|error| match error.as_str() {
+          Some("timeout") => ErrorKind::Timeout,
+          Some("connect") => ErrorKind::Connect,
+          _ => panic!("Unknown `{error:?}` error kind! Use timeout or connect"),
+        })
+        .collect(),
+      None => vec![ErrorKind::Timeout, ErrorKind::Connect],
+    };
+
+    Some(Retry {
+      attempts,
+      backoff,
+      delay: extract_duration(retry, "delay").unwrap_or(NDELAY),
+      statuses,
+      errors,
+    })
+  }
+
+  pub fn is_retryable(&self, response: &Result<reqwest::Response, reqwest::Error>) -> bool {
+    match response {
+      Ok(response) => self.is_retryable_status(response.status().as_u16()),
+      Err(error) => (error.is_timeout() && self.errors.contains(&ErrorKind::Timeout)) || (error.is_connect() && self.errors.contains(&ErrorKind::Connect)),
+    }
+  }
+
+  fn is_retryable_status(&self, status: u16) -> bool {
+    self.statuses.contains(&status)
+  }
+
+  // Delay before the next attempt, after the given failed attempt (starting at 1)
+  pub fn delay(&self, attempt: u64) -> Duration {
+    match self.backoff {
+      Backoff::Fixed => self.delay,
+      Backoff::Linear => self.delay * attempt as u32,
+      Backoff::Exponential => self.delay * 2u32.saturating_pow(attempt as u32 - 1),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn retry(text: &str) -> Option<Retry> {
+    let docs = crate::reader::read_file_as_yml_from_str(text);
+
+    Retry::new(&docs[0])
+  }
+
+  #[test]
+  fn parses_defaults() {
+    let policy = retry("---\nname: foo\nretry: {}").unwrap();
+
+    assert_eq!(policy.attempts, 3);
+    assert_eq!(policy.backoff, Backoff::Fixed);
+    assert_eq!(policy.delay(1), NDELAY);
+    assert_eq!(policy.delay(3), NDELAY);
+    assert!(policy.is_retryable_status(503));
+    assert!(!policy.is_retryable_status(404));
+    assert_eq!(policy.errors, vec![ErrorKind::Timeout, ErrorKind::Connect]);
+
+    assert!(retry("---\nname: foo").is_none());
+  }
+
+  #[test]
+  fn parses_policies() {
+    let policy = retry("---\nname: foo\nretry:\n  attempts: 5\n  backoff: exponential\n  delay: 200ms\n  statuses: [429]\n  errors: [timeout]").unwrap();
+
+    assert_eq!(policy.attempts, 5);
+    assert_eq!(policy.delay(1), Duration::from_millis(200));
+    assert_eq!(policy.delay(2), Duration::from_millis(400));
+    assert_eq!(policy.delay(3), Duration::from_millis(800));
+    assert!(policy.is_retryable_status(429));
+    assert!(!policy.is_retryable_status(503));
+    assert_eq!(policy.errors, vec![ErrorKind::Timeout]);
+
+    let policy = retry("---\nname: foo\nretry:\n  backoff: linear\n  delay: 1s").unwrap();
+
+    assert_eq!(policy.delay(1), Duration::from_secs(1));
+    assert_eq!(policy.delay(3), Duration::from_secs(3));
+  }
+
+  #[test]
+  #[should_panic(expected = "Unknown `random` backoff!")]
+  fn invalid_backoff() {
+    retry("---\nname: foo\nretry:\n  backoff: random");
+  }
+
+  #[test]
+  #[should_panic]
+  fn invalid_attempts() {
+    retry("---\nname: foo\nretry:\n  attempts: 0");
+  }
+}
@@ -0,0 +1,166 @@
+use async_trait::async_trait;
+use colored::*;
+use serde_json::{json, Value as JsonValue};
+use serde_yaml::Value;
+use tokio::time::sleep;
+
+use crate::actions::Runnable;
+use crate::actions::{extract_duration, extract_optional, yaml_to_json};
+use crate::benchmark::{Context, Pool, Reports};
+use crate::config::Config;
+use crate::interpolator;
+use crate::secrets;
+use crate::shared;
+
+use std::time::{Duration, Instant};
+
+static OPERATIONS: [&str; 4] = ["set", "incr", "push", "pop"];
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+pub struct Shared {
+  name: String,
+  operation: String,
+  key: String,
+  value: Option<JsonValue>,
+  by: i64,
+  wait: Option<Duration>,
+  assign: Option<String>,
+}
+
+impl Shared {
+  pub fn is_that_you(item: &Value) -> bool {
+    item.get("shared").and_then(|v| v.as_mapping()).is_some()
+  }
+
+  pub fn new(item: &Value) -> Shared {
+    let name = extract_optional(item, "name").unwrap_or_default();
+    let shared = item.get("shared").unwrap();
+
+    let operation = OPERATIONS.iter().find(|operation| shared.get(**operation).is_some()).unwrap_or_else(|| panic!("`shared` needs one of the {OPERATIONS:?} operations"));
+    let key = extract_optional(shared, operation).unwrap_or_else(|| panic!("`{operation}` needs the name of a shared variable"));
+    let value = shared.get("value").map(|value| yaml_to_json(value.clone()));
+
+    if value.is_none() && (*operation == "set" || *operation == "push") {
+      panic!("`{operation}` needs a `value`");
+    }
+
+    let by = match shared.get("by") {
+      Some(by) => by.as_i64().expect("`by` needs to be a number"),
+      None => 1,
+    };
+
+    Shared {
+      name,
+      operation: operation.to_string(),
+      key,
+      value,
+      by,
+      wait: extract_duration(shared, "wait"),
+      assign: extract_optional(item, "assign"),
+    }
+  }
+
+  fn value(&self, context: &Context, config: &Config) -> JsonValue {
+    match &self.value {
+      // Values made of a single interpolation keep their JSON type, like `value: "{{ order_id }}"`
+      Some(JsonValue::String(value)) => interpolator::Interpolator::new(context).resolve_value(value, !config.relaxed_interpolations),
+      Some(value) => value.clone(),
+      None => JsonValue::Null,
+    }
+  }
+
+  async fn pop(&self) -> Option<JsonValue> {
+    let begin = Instant::now();
+
+    loop {
+      let value = shared::pop(&self.key);
+
+      match self.wait {
+        Some(wait) if value.is_none() && begin.elapsed() < wait => sleep(POLL_INTERVAL).await,
+        _ => return value,
+      }
+    }
+  }
+}
+
+#[async_trait]
+impl Runnable for Shared {
+  async fn execute(&self, context: &mut Context, _reports: &mut Reports, _pool: &Pool, config: &Config) {
+    let result = match self.operation.as_str() {
+      "set" => {
+        let value = self.value(context, config);
+        shared::set(&self.key, value.clone());
+
+        value
+      }
+      "incr" => json!(shared::incr(&self.key, self.by)),
+      "push" => json!(shared::push(&self.key, self.value(context, config))),
+      _ => match self.pop().await {
+        Some(value) => value,
+        None => {
+          if !config.quiet {
+            println!("{:width$} {} {}", self.name.green(), "empty queue".yellow(), self.key.cyan().bold(), width = 25);
+          }
+
+          JsonValue::Null
+        }
+      },
+    };
+
+    if !config.quiet {
+      println!("{:width$} {} {}={}", self.name.green(), self.operation.yellow(), self.key.cyan().bold(), secrets::redact(&result.to_string()).magenta(), width = 25);
+    }
+
+    if let Some(ref key) = self.assign {
+      context.insert(key.to_owned(), result);
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn parses_operations() {
+    let docs = crate::reader::read_file_as_yml_from_str("---\nname: Count\nshared:\n  incr: visits\n  by: 2\nassign: visits");
+    let shared = Shared::new(&docs[0]);
+
+    assert!(Shared::is_that_you(&docs[0]));
+    assert_eq!(shared.operation, "incr");
+    assert_eq!(shared.key, "visits");
+    assert_eq!(shared.by, 2);
+    assert_eq!(shared.assign, Some("visits".to_string()));
+
+    let docs = crate::reader::read_file_as_yml_from_str("---\nname: Consume\nshared:\n  pop: orders\n  wait: 2s");
+    let shared = Shared::new(&docs[0]);
+
+    assert_eq!(shared.operation, "pop");
+    assert_eq!(shared.wait, Some(Duration::from_secs(2)));
+  }
+
+  #[test]
+  fn resolves_values() {
+    let mut context = Context::new();
+    context.insert("order_id".to_string(), json!(42));
+    context.insert("user".to_string(), json!({"id": 7}));
+
+    let value = |text: &str| {
+      let docs = crate::reader::read_file_as_yml_from_str(text);
+
+      Shared::new(&docs[0]).value(&context, &Config::test())
+    };
+
+    assert_eq!(value("---\nname: Produce\nshared:\n  push: orders\n  value: '{{ order_id }}'"), json!(42));
+    assert_eq!(value("---\nname: Produce\nshared:\n  push: orders\n  value: 'order-{{ order_id }}'"), json!("order-42"));
+    assert_eq!(value("---\nname: Share\nshared:\n  set: user\n  value: '{{ user }}'"), json!({"id": 7}));
+    assert_eq!(value("---\nname: Share\nshared:\n  set: limit\n  value: 10"), json!(10));
+  }
+
+  #[test]
+  #[should_panic(expected = "`push` needs a `value`")]
+  fn push_without_value() {
+    let docs = crate::reader::read_file_as_yml_from_str("---\nname: Produce\nshared:\n  push: orders");
+    Shared::new(&docs[0]);
+  }
+}
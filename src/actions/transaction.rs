@@ -0,0 +1,105 @@
+use async_trait::async_trait;
+use colored::*;
+use serde_yaml::Value;
+
+use crate::actions::{extract, step_failed};
+use crate::actions::{Report, Runnable};
+use crate::benchmark::{Benchmark, Context, Pool, Reports};
+use crate::config::Config;
+
+use std::time::Instant;
+
+pub struct Transaction {
+  name: String,
+  items: Benchmark,
+}
+
+impl Transaction {
+  pub fn is_that_you(item: &Value) -> bool {
+    item.get("transaction").and_then(|v| v.as_sequence()).is_some()
+  }
+
+  pub fn new(item: &Value, items: Benchmark) -> Transaction {
+    let name = extract(item, "name");
+
+    Transaction {
+      name,
+      items,
+    }
+  }
+}
+
+#[async_trait]
+impl Runnable for Transaction {
+  async fn execute(&self, context: &mut Context, reports: &mut Reports, pool: &Pool, config: &Config) {
+    let begin = Instant::now();
+    let mut failed = false;
+
+    for item in self.items.iter() {
+      let first_report = reports.len();
+
+      item.execute(context, reports, pool, config).await;
+
+      failed |= step_failed(&reports[first_report..]);
+    }
+
+    let duration_ms = begin.elapsed().as_secs_f64() * 1000.0;
+
+    if !config.quiet {
+      let status_text = if failed {
+        "failed".red()
+      } else {
+        "completed".yellow()
+      };
+
+      println!("{:width$} {} {}", self.name.green(), status_text, format!("{}ms", duration_ms.round()).cyan(), width = 25);
+    }
+
+    reports.push(Report {
+      name: format!("{} (transaction)", self.name),
+      duration: duration_ms,
+      status: if failed {
+        520
+      } else {
+        200
+      },
+      error: None,
+      max_duration: None,
+      aggregate: true,
+      retried: false,
+    });
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::actions::Stub;
+  use crate::benchmark::PoolStore;
+  use std::sync::{Arc, Mutex};
+
+  #[test]
+  fn reports_children_and_the_whole_transaction() {
+    let item = &crate::reader::read_file_as_yml_from_str("---\nname: Checkout\ntransaction: []")[0];
+    let items: Benchmark = vec![
+      Box::new(Stub {
+        name: "Checkout",
+        status: 200,
+      }),
+      Box::new(Stub {
+        name: "Pay",
+        status: 200,
+      }),
+    ];
+    let transaction = Transaction::new(item, items);
+    let pool: Pool = Arc::new(Mutex::new(PoolStore::new()));
+    let mut reports = Reports::new();
+
+    futures::executor::block_on(transaction.execute(&mut Context::new(), &mut reports, &pool, &Config::test()));
+
+    let names: Vec<&str> = reports.iter().map(|r| r.name.as_str()).collect();
+    assert_eq!(names, vec!["Checkout", "Pay", "Checkout (transaction)"]);
+    assert!(reports[2].aggregate);
+    assert!(reports[2].is_successful());
+  }
+}
@@ -0,0 +1,116 @@
+use async_trait::async_trait;
+use colored::*;
+use serde_yaml::Value;
+use tokio::time::sleep;
+
+use crate::actions::condition::Condition;
+use crate::actions::{extract, extract_duration};
+use crate::actions::{Report, Runnable};
+use crate::benchmark::{Benchmark, Context, Pool, Reports};
+use crate::config::Config;
+
+use std::time::{Duration, Instant};
+
+const NRETRIES: u64 = 3;
+const NDELAY: Duration = Duration::from_secs(1);
+
+pub struct Until {
+  name: String,
+  condition: Condition,
+  retries: u64,
+  delay: Duration,
+  items: Benchmark,
+}
+
+impl Until {
+  pub fn is_that_you(item: &Value) -> bool {
+    item.get("until").is_some()
+  }
+
+  pub fn new(item: &Value, items: Benchmark) -> Until {
+    let name = extract(item, "name");
+    let condition = Condition::new(item, "until");
+    let retries = match item.get("retries") {
+      Some(retries) => retries.as_u64().expect("`retries` needs to be a positive number"),
+      None => NRETRIES,
+    };
+
+    let delay = extract_duration(item, "delay").unwrap_or(NDELAY);
+
+    Until {
+      name,
+      condition,
+      retries,
+      delay,
+      items,
+    }
+  }
+}
+
+#[async_trait]
+impl Runnable for Until {
+  async fn execute(&self, context: &mut Context, reports: &mut Reports, pool: &Pool, config: &Config) {
+    let begin = Instant::now();
+    let mut attempt = 0;
+
+    let satisfied = loop {
+      for item in self.items.iter() {
+        item.execute(context, reports, pool, config).await;
+      }
+
+      if self.condition.is_satisfied(context, config) {
+        break true;
+      }
+
+      if attempt == self.retries {
+        break false;
+      }
+
+      attempt += 1;
+
+      if !config.quiet {
+        println!("{:width$} {} {}/{}", self.name.green(), "retrying".yellow(), attempt.to_string().cyan().bold(), self.retries.to_string().cyan(), width = 25);
+      }
+
+      sleep(self.delay).await;
+    };
+
+    let duration_ms = begin.elapsed().as_secs_f64() * 1000.0;
+
+    if !satisfied && !config.quiet {
+      println!("{:width$} {} {}", self.name.green(), "gave up waiting for".red(), self.condition.source.magenta(), width = 25);
+    }
+
+    reports.push(Report {
+      name: format!("{} (loop)", self.name),
+      duration: duration_ms,
+      status: if satisfied {
+        200
+      } else {
+        520
+      },
+      error: None,
+      max_duration: None,
+      aggregate: true,
+      retried: false,
+    });
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn delay(text: &str) -> Duration {
+    let item = &crate::reader::read_file_as_yml_from_str(text)[0];
+
+    Until::new(item, Benchmark::new()).delay
+  }
+
+  #[test]
+  fn reads_delays_like_other_durations() {
+    assert_eq!(delay("---\nname: Poll\nuntil: iteration > 1\ndelay: 2s"), Duration::from_secs(2));
+    assert_eq!(delay("---\nname: Poll\nuntil: iteration > 1\ndelay: 250"), Duration::from_millis(250));
+    assert_eq!(delay("---\nname: Poll\nuntil: iteration > 1"), Duration::from_secs(1));
+  }
+}
@@ -0,0 +1,49 @@
+use async_trait::async_trait;
+use colored::*;
+use serde_yaml::Value;
+
+use crate::actions::condition::Condition;
+use crate::actions::extract_optional;
+use crate::actions::Runnable;
+use crate::benchmark::{Benchmark, Context, Pool, Reports};
+use crate::config::Config;
+
+pub struct When {
+  name: String,
+  condition: Condition,
+  items: Benchmark,
+}
+
+impl When {
+  pub fn is_that_you(item: &Value) -> bool {
+    item.get("when").is_some()
+  }
+
+  pub fn new(item: &Value, items: Benchmark) -> When {
+    let name = extract_optional(item, "name").unwrap_or_default();
+    let condition = Condition::new(item, "when");
+
+    When {
+      name,
+      condition,
+      items,
+    }
+  }
+}
+
+#[async_trait]
+impl Runnable for When {
+  async fn execute(&self, context: &mut Context, reports: &mut Reports, pool: &Pool, config: &Config) {
+    if !self.condition.is_satisfied(context, config) {
+      if !config.quiet {
+        println!("{:width$} {} {}", self.name.green(), "skipped".yellow(), self.condition.source.magenta(), width = 25);
+      }
+
+      return;
+    }
+
+    for item in self.items.iter() {
+      item.execute(context, reports, pool, config).await;
+    }
+  }
+}
@@ -3,13 +3,18 @@ use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
 use futures::stream::{self, StreamExt};
+use futures::FutureExt;
+use std::panic::AssertUnwindSafe;
 
 use serde_json::{json, Map, Value};
 use tokio::{runtime, time::sleep};
 
-use crate::actions::{Report, Runnable};
+use crate::actions::{step_failed, Report, Runnable};
 use crate::config::Config;
 use crate::expandable::include;
+use crate::reader;
+use crate::scenario::Scenarios;
+use crate::secrets;
 use crate::tags::Tags;
 use crate::writer;
 
@@ -28,25 +33,107 @@ pub struct BenchmarkResult {
   pub duration: f64,
 }
 
-async fn run_iteration(benchmark: Arc<Benchmark>, pool: Pool, config: Arc<Config>, iteration: i64) -> Vec<Report> {
+async fn run_iteration(scenarios: Arc<Scenarios>, setup_context: Arc<Context>, pool: Pool, config: Arc<Config>, iteration: i64) -> Vec<Report> {
   if config.rampup > 0 {
     let delay = config.rampup / config.iterations;
     sleep(Duration::new((delay * iteration) as u64, 0)).await;
   }
 
-  let mut context: Context = Context::new();
+  // Iterations get their own copy of the variables assigned in the setup
+  let mut context: Context = setup_context.as_ref().clone();
   let mut reports: Vec<Report> = Vec::new();
 
-  context.insert("iteration".to_string(), json!(iteration.to_string()));
+  context.insert("iteration".to_string(), json!(iteration));
   context.insert("base".to_string(), json!(config.base.to_string()));
 
-  for item in benchmark.iter() {
+  let scenario = scenarios.pick();
+  let begin = Instant::now();
+  let mut failed = false;
+
+  if let Some(name) = &scenario.name {
+    context.insert("scenario".to_string(), json!(name));
+  }
+
+  for item in scenario.benchmark.iter() {
+    let first_report = reports.len();
+
     item.execute(&mut context, &mut reports, &pool, &config).await;
+
+    failed |= step_failed(&reports[first_report..]);
+  }
+
+  // Scenarios are reported as a whole to break down the stats per scenario
+  if let Some(name) = &scenario.name {
+    reports.push(Report {
+      name: format!("{name} (scenario)"),
+      duration: begin.elapsed().as_secs_f64() * 1000.0,
+      status: if failed {
+        520
+      } else {
+        200
+      },
+      error: None,
+      max_duration: None,
+      aggregate: true,
+      retried: false,
+    });
+  }
+
+  reports
+}
+
+// Setup and teardown items run once for all the iterations. Tags don't apply to them.
+fn load_phase(benchmark_path: &str, phase: &str) -> Benchmark {
+  let docs = reader::read_file_as_yml(benchmark_path);
+  let mut benchmark: Benchmark = Benchmark::new();
+
+  if docs[0].get(phase).is_some() {
+    include::expand_items(benchmark_path, reader::read_yaml_doc_accessor(&docs[0], Some(phase)), &mut benchmark, &Tags::new(None, None));
+  }
+
+  benchmark
+}
+
+// Phases are run outside the measured window
+async fn run_phase(benchmark: &Benchmark, context: &mut Context, pool: &Pool, config: &Config) -> Reports {
+  let mut reports: Vec<Report> = Vec::new();
+
+  for item in benchmark.iter() {
+    item.execute(context, &mut reports, pool, config).await;
   }
 
   reports
 }
 
+// Runs the setup once, then the measured iterations, and the teardown once, even if an
+// iteration panics. Reports of the phases are only kept with `phase_stats`
+async fn run_benchmark(scenarios: Arc<Scenarios>, setup: &Benchmark, teardown: &Benchmark, pool: Pool, config: Arc<Config>, iterations: i64) -> (Vec<Reports>, f64) {
+  let mut setup_context: Context = config.vars.clone();
+  setup_context.insert("base".to_string(), json!(config.base.to_string()));
+
+  let setup_reports = run_phase(setup, &mut setup_context, &pool, &config).await;
+
+  let setup_context = Arc::new(setup_context);
+  let children = (0..iterations).map(|iteration| run_iteration(scenarios.clone(), setup_context.clone(), pool.clone(), config.clone(), iteration));
+  let buffered = stream::iter(children).buffer_unordered(config.concurrency as usize);
+
+  let begin = Instant::now();
+  let result = AssertUnwindSafe(buffered.collect::<Vec<_>>()).catch_unwind().await;
+  let duration = begin.elapsed().as_secs_f64();
+
+  let teardown_reports = run_phase(teardown, &mut setup_context.as_ref().clone(), &pool, &config).await;
+
+  let mut reports = result.unwrap_or_else(|panic| std::panic::resume_unwind(panic));
+
+  if config.phase_stats {
+    reports.insert(0, setup_reports);
+    reports.push(teardown_reports);
+    reports.retain(|reports| !reports.is_empty());
+  }
+
+  (reports, duration)
+}
+
 fn join<S: ToString>(l: Vec<S>, sep: &str) -> String {
   l.iter().fold(
     "".to_string(),
@@ -55,8 +142,20 @@ fn join<S: ToString>(l: Vec<S>, sep: &str) -> String {
 }
 
 #[allow(clippy::too_many_arguments)]
-pub fn execute(benchmark_path: &str, report_path_option: Option<&str>, relaxed_interpolations: bool, no_check_certificate: bool, quiet: bool, nanosec: bool, timeout: Option<&str>, verbose: bool, tags: &Tags) -> BenchmarkResult {
-  let config = Arc::new(Config::new(benchmark_path, relaxed_interpolations, no_check_certificate, quiet, nanosec, timeout.map_or(10, |t| t.parse().unwrap_or(10)), verbose));
+pub fn execute(
+  benchmark_path: &str,
+  report_path_option: Option<&str>,
+  relaxed_interpolations: bool,
+  no_check_certificate: bool,
+  quiet: bool,
+  nanosec: bool,
+  timeout: Option<&str>,
+  verbose: bool,
+  tags: &Tags,
+  vars: Context,
+  environment: Option<&str>,
+) -> BenchmarkResult {
+  let config = Arc::new(Config::new(benchmark_path, relaxed_interpolations, no_check_certificate, quiet, nanosec, timeout.map_or(10, |t| t.parse().unwrap_or(10)), verbose, vars, environment));
 
   if report_path_option.is_some() {
     println!("{}: {}. Ignoring {} and {} properties...", "Report mode".yellow(), "on".purple(), "concurrency".yellow(), "iterations".yellow());
@@ -66,44 +165,43 @@ pub fn execute(benchmark_path: &str, report_path_option: Option<&str>, relaxed_i
     println!("{} {}", "Rampup".yellow(), config.rampup.to_string().purple());
   }
 
-  println!("{} {}", "Base URL".yellow(), config.base.purple());
+  println!("{} {}", "Base URL".yellow(), secrets::redact(&config.base).purple());
   println!();
 
   let threads = std::cmp::min(num_cpus::get(), config.concurrency as usize);
   let rt = runtime::Builder::new_current_thread().enable_all().worker_threads(threads).build().unwrap();
 
   rt.block_on(async {
-    let mut benchmark: Benchmark = Benchmark::new();
+    let scenarios = Scenarios::load(benchmark_path, tags);
     let pool_store: PoolStore = PoolStore::new();
 
-    include::expand_from_filepath(benchmark_path, &mut benchmark, Some("plan"), tags);
-
-    if benchmark.is_empty() {
+    if scenarios.is_empty() {
       eprintln!("Empty benchmark. Exiting.");
       std::process::exit(1);
     }
 
-    let benchmark = Arc::new(benchmark);
+    let setup = load_phase(benchmark_path, "setup");
+    let teardown = load_phase(benchmark_path, "teardown");
+
+    let scenarios = Arc::new(scenarios);
     let pool = Arc::new(Mutex::new(pool_store));
 
-    if let Some(report_path) = report_path_option {
-      let reports = run_iteration(benchmark.clone(), pool.clone(), config, 0).await;
+    // Reports are recorded from a single iteration
+    let iterations = if report_path_option.is_some() {
+      1
+    } else {
+      config.iterations
+    };
+    let (reports, duration) = run_benchmark(scenarios, &setup, &teardown, pool, config.clone(), iterations).await;
 
-      writer::write_file(report_path, join(reports, ""));
+    if let Some(report_path) = report_path_option {
+      writer::write_file(report_path, join(reports.concat(), ""));
 
       BenchmarkResult {
         reports: vec![],
         duration: 0.0,
       }
     } else {
-      let children = (0..config.iterations).map(|iteration| run_iteration(benchmark.clone(), pool.clone(), config.clone(), iteration));
-
-      let buffered = stream::iter(children).buffer_unordered(config.concurrency as usize);
-
-      let begin = Instant::now();
-      let reports: Vec<Vec<Report>> = buffered.collect::<Vec<_>>().await;
-      let duration = begin.elapsed().as_secs_f64();
-
       BenchmarkResult {
         reports,
         duration,
@@ -111,3 +209,98 @@ pub fn execute(benchmark_path: &str, report_path_option: Option<&str>, relaxed_i
     }
   })
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use async_trait::async_trait;
+
+  type Log = Arc<Mutex<Vec<(String, Option<Value>)>>>;
+
+  // Item recording its runs with the `token` variable it sees, which can also assign it or panic
+  struct Probe {
+    name: &'static str,
+    log: Log,
+    assign: Option<&'static str>,
+    panics: bool,
+  }
+
+  #[async_trait]
+  impl Runnable for Probe {
+    async fn execute(&self, context: &mut Context, reports: &mut Reports, _pool: &Pool, _config: &Config) {
+      self.log.lock().unwrap().push((self.name.to_string(), context.get("token").cloned()));
+
+      if let Some(token) = self.assign {
+        context.insert("token".to_string(), json!(token));
+      }
+
+      if self.panics {
+        panic!("Assertion failed: {}", self.name);
+      }
+
+      reports.push(Report {
+        name: self.name.to_string(),
+        duration: 1.0,
+        status: 200,
+        error: None,
+        max_duration: None,
+        aggregate: false,
+        retried: false,
+      });
+    }
+  }
+
+  fn probe(name: &'static str, log: &Log, assign: Option<&'static str>, panics: bool) -> Benchmark {
+    vec![Box::new(Probe {
+      name,
+      log: log.clone(),
+      assign,
+      panics,
+    })]
+  }
+
+  fn run(log: &Log, panics: bool, config: Config) -> (Vec<Reports>, f64) {
+    let scenarios = Arc::new(Scenarios::single(probe("plan", log, Some("changed"), panics)));
+    let setup = probe("setup", log, Some("secret"), false);
+    let teardown = probe("teardown", log, None, false);
+    let pool: Pool = Arc::new(Mutex::new(PoolStore::new()));
+
+    futures::executor::block_on(run_benchmark(scenarios, &setup, &teardown, pool, Arc::new(config), 3))
+  }
+
+  #[test]
+  fn runs_setup_once_and_shares_its_context() {
+    let log: Log = Arc::new(Mutex::new(Vec::new()));
+    let (reports, _) = run(&log, false, Config::test());
+
+    let token = Some(json!("secret"));
+    assert_eq!(*log.lock().unwrap(), vec![("setup".to_string(), None), ("plan".to_string(), token.clone()), ("plan".to_string(), token.clone()), ("plan".to_string(), token.clone()), ("teardown".to_string(), token)]);
+
+    // Phases are excluded from the stats by default
+    assert_eq!(reports.len(), 3);
+    assert!(reports.iter().flatten().all(|report| report.name == "plan"));
+  }
+
+  #[test]
+  fn includes_phases_in_stats() {
+    let log: Log = Arc::new(Mutex::new(Vec::new()));
+    let config = Config {
+      phase_stats: true,
+      ..Config::test()
+    };
+
+    let (reports, _) = run(&log, false, config);
+    let names: Vec<&str> = reports.iter().flatten().map(|report| report.name.as_str()).collect();
+
+    assert_eq!(names, vec!["setup", "plan", "plan", "plan", "teardown"]);
+  }
+
+  #[test]
+  fn runs_teardown_after_failed_iterations() {
+    let log: Log = Arc::new(Mutex::new(Vec::new()));
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| run(&log, true, Config::test())));
+
+    assert!(result.is_err());
+    assert_eq!(log.lock().unwrap().last().map(|(name, _)| name.as_str()), Some("teardown"));
+  }
+}
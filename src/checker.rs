@@ -1,4 +1,7 @@
+use std::collections::HashMap;
+
 use colored::*;
+use serde_yaml::Value;
 
 use crate::actions::Report;
 use crate::reader;
@@ -11,14 +14,26 @@ pub fn compare(list_reports: &[Vec<Report>], filepath: &str, threshold: &str) ->
 
   let docs = reader::read_file_as_yml(filepath);
   let doc = &docs[0];
-  let items = doc.as_sequence().unwrap();
+  let recorded = recorded_durations(doc.as_sequence().unwrap());
   let mut slow_counter = 0;
 
   println!();
 
   for report in list_reports {
-    for (i, report_item) in report.iter().enumerate() {
-      let recorded_duration = items[i].get("duration").and_then(|v| v.as_f64()).unwrap();
+    // Reports are matched by name, and by order amongst the reports with the same name, since
+    // scenarios, retries, conditionals or loops change the number and order of the reports
+    let mut occurrences: HashMap<&str, usize> = HashMap::new();
+
+    for report_item in report.iter().filter(|r| !r.retried) {
+      let occurrence = occurrences.entry(&report_item.name).or_insert(0);
+      let recorded_duration = recorded.get(&report_item.name).and_then(|durations| durations.get(*occurrence));
+
+      *occurrence += 1;
+
+      let Some(recorded_duration) = recorded_duration else {
+        continue;
+      };
+
       let delta_ms = report_item.duration - recorded_duration;
 
       if delta_ms > threshold_value {
@@ -35,3 +50,49 @@ pub fn compare(list_reports: &[Vec<Report>], filepath: &str, threshold: &str) ->
     Err(slow_counter)
   }
 }
+
+fn recorded_durations(items: &[Value]) -> HashMap<String, Vec<f64>> {
+  let mut recorded: HashMap<String, Vec<f64>> = HashMap::new();
+
+  for item in items.iter().filter(|item| !item.get("retried").and_then(|v| v.as_bool()).unwrap_or(false)) {
+    let name = item.get("name").and_then(|v| v.as_str()).unwrap_or_default();
+    let duration = item.get("duration").and_then(|v| v.as_f64()).unwrap();
+
+    recorded.entry(name.to_string()).or_default().push(duration);
+  }
+
+  recorded
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::io::Write;
+  use tempfile::NamedTempFile;
+
+  fn report(name: &str, duration: f64) -> Report {
+    Report {
+      name: name.to_string(),
+      duration,
+      status: 200,
+      error: None,
+      max_duration: None,
+      aggregate: false,
+      retried: false,
+    }
+  }
+
+  #[test]
+  fn compares_reports_by_name() {
+    let mut file = NamedTempFile::new().unwrap();
+    file.write_all(b"\n- name: Login\n  duration: 10.0\n  status: 200\n\n- name: Fetch\n  duration: 20.0\n  status: 200\n\n- name: Fetch\n  duration: 30.0\n  status: 200\n").unwrap();
+    let path = file.path().to_str().unwrap();
+
+    // Reordered, missing and unknown reports don't break the comparison
+    let reports = vec![vec![report("Fetch", 25.0), report("Unknown", 500.0), report("Login", 12.0), report("Fetch", 35.0), report("Fetch", 500.0)]];
+    assert_eq!(compare(&reports, path, "10"), Ok(()));
+
+    let reports = vec![vec![report("Fetch", 25.0), report("Fetch", 45.0)], vec![report("Login", 30.0)]];
+    assert_eq!(compare(&reports, path, "10"), Err(2));
+  }
+}
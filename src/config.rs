@@ -1,8 +1,14 @@
+use std::collections::HashMap;
+
 use serde_yaml::Value;
 
+use crate::actions::yaml_to_json;
 use crate::benchmark::Context;
+use crate::environment::Environment;
+use crate::faker;
 use crate::interpolator;
 use crate::reader;
+use crate::secrets;
 
 const NITERATIONS: i64 = 1;
 const NRAMPUP: i64 = 0;
@@ -18,20 +24,41 @@ pub struct Config {
   pub nanosec: bool,
   pub timeout: u64,
   pub verbose: bool,
+  pub vars: Context,
+  pub headers: HashMap<String, String>,
+  pub phase_stats: bool,
 }
 
 impl Config {
-  pub fn new(path: &str, relaxed_interpolations: bool, no_check_certificate: bool, quiet: bool, nanosec: bool, timeout: u64, verbose: bool) -> Config {
+  #[allow(clippy::too_many_arguments)]
+  pub fn new(path: &str, relaxed_interpolations: bool, no_check_certificate: bool, quiet: bool, nanosec: bool, timeout: u64, verbose: bool, vars: Context, environment: Option<&str>) -> Config {
     let config_docs = reader::read_file_as_yml(path);
     let config_doc = &config_docs[0];
 
-    let context: Context = Context::new();
-    let interpolator = interpolator::Interpolator::new(&context);
+    let environment = environment.map(|name| Environment::new(config_doc, name));
+
+    let secrets = secrets::load(config_doc, path, &vars);
+
+    // Variables of the environment profile and secrets can be overridden from the command line
+    let profile_vars = environment.as_ref().map(|environment| environment.vars.clone()).unwrap_or_default();
+    let vars: Context = profile_vars.into_iter().chain(secrets).chain(vars).collect();
+
+    interpolator::set_load_context(vars.clone());
+
+    let interpolator = interpolator::Interpolator::new(&vars);
 
     let iterations = read_i64_configuration(config_doc, &interpolator, "iterations", NITERATIONS);
     let concurrency = read_i64_configuration(config_doc, &interpolator, "concurrency", iterations);
     let rampup = read_i64_configuration(config_doc, &interpolator, "rampup", NRAMPUP);
-    let base = read_str_configuration(config_doc, &interpolator, "base", "");
+    let base = match environment.as_ref().and_then(|environment| environment.base.as_deref()) {
+      Some(base) => interpolator.resolve(base, true),
+      None => read_str_configuration(config_doc, &interpolator, "base", ""),
+    };
+    let headers = environment.map(|environment| environment.headers).unwrap_or_default();
+
+    let phase_stats = config_doc.get("phase_stats").and_then(|v| v.as_bool()).unwrap_or(false);
+
+    faker::configure(config_doc);
 
     if concurrency > iterations {
       panic!("The concurrency can not be higher than the number of iterations")
@@ -48,8 +75,57 @@ impl Config {
       nanosec,
       timeout,
       verbose,
+      vars,
+      headers,
+      phase_stats,
+    }
+  }
+}
+
+// Quiet configuration for the tests running items without a benchmark file
+#[cfg(test)]
+impl Config {
+  pub fn test() -> Config {
+    Config {
+      base: String::new(),
+      concurrency: 1,
+      iterations: 1,
+      relaxed_interpolations: false,
+      no_check_certificate: false,
+      rampup: 0,
+      quiet: true,
+      nanosec: false,
+      timeout: 10,
+      verbose: false,
+      vars: Context::new(),
+      headers: HashMap::new(),
+      phase_stats: false,
+    }
+  }
+}
+
+// Reads the variables given with `--vars-file` and `--var name=value`, which seed the context
+// of every iteration. Variables given with `--var` win over the ones in the file
+pub fn read_vars(vars: &[&str], vars_file: Option<&str>) -> Context {
+  let mut context = Context::new();
+
+  if let Some(path) = vars_file {
+    let docs = reader::read_file_as_yml(path);
+
+    match docs.first().map(|doc| yaml_to_json(doc.clone())) {
+      Some(serde_json::Value::Object(map)) => context.extend(map),
+      _ => panic!("The vars file {path} needs to be a mapping of variables"),
     }
   }
+
+  for var in vars {
+    match var.split_once('=') {
+      Some((name, value)) if !name.trim().is_empty() => context.insert(name.trim().to_string(), serde_json::json!(value)),
+      _ => panic!("Invalid '{var}' variable! Use name=value"),
+    };
+  }
+
+  context
 }
 
 fn read_str_configuration(config_doc: &Value, interpolator: &interpolator::Interpolator, name: &str, default: &str) -> String {
@@ -75,7 +151,7 @@ fn read_i64_configuration(config_doc: &Value, interpolator: &interpolator::Inter
   let value = if let Some(value) = config_doc.get(name).and_then(|v| v.as_i64()) {
     Some(value)
   } else if let Some(key) = config_doc.get(name).and_then(|v| v.as_str()) {
-    interpolator.resolve(key, false).parse::<i64>().ok()
+    interpolator.resolve(key, true).parse::<i64>().ok()
   } else {
     None
   };
@@ -99,3 +175,30 @@ fn read_i64_configuration(config_doc: &Value, interpolator: &interpolator::Inter
     }
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use serde_json::json;
+  use std::io::Write;
+  use tempfile::NamedTempFile;
+
+  #[test]
+  fn reads_vars() {
+    let mut file = NamedTempFile::new().unwrap();
+    file.write_all(b"user: alice\nlimit: 10\nids: [1, 2]\n").unwrap();
+
+    let vars = read_vars(&["user=bob", "query=a=b"], file.path().to_str());
+
+    assert_eq!(vars.get("user"), Some(&json!("bob")));
+    assert_eq!(vars.get("limit"), Some(&json!(10)));
+    assert_eq!(vars.get("ids"), Some(&json!([1, 2])));
+    assert_eq!(vars.get("query"), Some(&json!("a=b")));
+  }
+
+  #[test]
+  #[should_panic(expected = "Invalid 'user' variable! Use name=value")]
+  fn invalid_var() {
+    read_vars(&["user"], None);
+  }
+}
@@ -0,0 +1,138 @@
+use std::collections::HashMap;
+use std::fs;
+
+use serde_yaml::Value;
+
+use crate::actions::yaml_to_json;
+use crate::benchmark::Context;
+
+// Environment profile selected with `--env`, overriding the base, headers and variables of the benchmark
+pub struct Environment {
+  pub base: Option<String>,
+  pub headers: HashMap<String, String>,
+  pub vars: Context,
+}
+
+impl Environment {
+  pub fn new(doc: &Value, name: &str) -> Environment {
+    let profile = match doc.get("environments").and_then(|v| v.as_mapping()) {
+      Some(environments) => environments.get(name).unwrap_or_else(|| panic!("Unknown '{name}' environment! Available environments: {}", names(environments))),
+      None => panic!("Unknown '{name}' environment! The benchmark has no environments"),
+    };
+
+    let base = match profile.get("base") {
+      Some(Value::String(base)) => Some(base.to_owned()),
+      Some(_) => panic!("The base of the '{name}' environment needs to be a string"),
+      None => None,
+    };
+
+    let mut headers = HashMap::new();
+
+    if let Some(mapping) = profile.get("headers").and_then(|v| v.as_mapping()) {
+      for (key, val) in mapping.iter() {
+        match (key.as_str(), val.as_str()) {
+          (Some(key), Some(val)) => headers.insert(key.to_string(), val.to_string()),
+          _ => panic!("The headers of the '{name}' environment need to be strings"),
+        };
+      }
+    }
+
+    let vars = match profile.get("vars").map(|v| yaml_to_json(v.clone())) {
+      Some(serde_json::Value::Object(vars)) => vars,
+      Some(_) => panic!("The vars of the '{name}' environment need to be a mapping"),
+      None => Context::new(),
+    };
+
+    Environment {
+      base,
+      headers,
+      vars,
+    }
+  }
+}
+
+fn names(environments: &serde_yaml::Mapping) -> String {
+  environments.keys().filter_map(|key| key.as_str()).collect::<Vec<_>>().join(", ")
+}
+
+// Loads the variables of a `.env` file into the environment. Variables already set in the
+// environment win over the ones in the file
+pub fn load_env_file(path: &str) {
+  let content = fs::read_to_string(path).unwrap_or_else(|_| panic!("Unable to read the env file {path}"));
+
+  for (key, value) in parse_env_file(&content) {
+    if std::env::var_os(&key).is_none() {
+      std::env::set_var(key, value);
+    }
+  }
+}
+
+fn parse_env_file(content: &str) -> Vec<(String, String)> {
+  content
+    .lines()
+    .map(|line| line.trim())
+    .filter(|line| !line.is_empty() && !line.starts_with('#'))
+    .map(|line| {
+      let line = line.strip_prefix("export ").unwrap_or(line);
+
+      match line.split_once('=') {
+        Some((key, value)) => (key.trim().to_string(), unquote(value.trim()).to_string()),
+        None => panic!("Invalid '{line}' line in the env file! Use NAME=value"),
+      }
+    })
+    .collect()
+}
+
+fn unquote(value: &str) -> &str {
+  for quote in ['"', '\''] {
+    if let Some(value) = value.strip_prefix(quote).and_then(|v| v.strip_suffix(quote)) {
+      return value;
+    }
+  }
+
+  value
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use serde_json::json;
+
+  #[test]
+  fn parses_env_files() {
+    let content = "# comment\n\nHOST=http://localhost:9000\nexport TOKEN=\"a b\"\nNAME='drill'\nQUERY=a=b\n";
+
+    let vars = parse_env_file(content);
+
+    assert_eq!(vars[0], ("HOST".to_string(), "http://localhost:9000".to_string()));
+    assert_eq!(vars[1], ("TOKEN".to_string(), "a b".to_string()));
+    assert_eq!(vars[2], ("NAME".to_string(), "drill".to_string()));
+    assert_eq!(vars[3], ("QUERY".to_string(), "a=b".to_string()));
+    assert_eq!(vars.len(), 4);
+  }
+
+  #[test]
+  fn loads_environments() {
+    let text = "---\nbase: http://localhost\nenvironments:\n  staging:\n    base: https://staging.example.com\n    headers:\n      X-Env: staging\n    vars:\n      users: 10\n  local: {}\n";
+    let doc = &crate::reader::read_file_as_yml_from_str(text)[0];
+
+    let staging = Environment::new(doc, "staging");
+    assert_eq!(staging.base, Some("https://staging.example.com".to_string()));
+    assert_eq!(staging.headers.get("X-Env"), Some(&"staging".to_string()));
+    assert_eq!(staging.vars.get("users"), Some(&json!(10)));
+
+    let local = Environment::new(doc, "local");
+    assert_eq!(local.base, None);
+    assert!(local.headers.is_empty());
+    assert!(local.vars.is_empty());
+  }
+
+  #[test]
+  #[should_panic(expected = "Unknown 'perf' environment! Available environments: staging")]
+  fn unknown_environment() {
+    let text = "---\nenvironments:\n  staging:\n    base: https://staging.example.com\n";
+    let doc = &crate::reader::read_file_as_yml_from_str(text)[0];
+
+    Environment::new(doc, "perf");
+  }
+}
@@ -0,0 +1,159 @@
+use serde_yaml::{Mapping, Value};
+
+use crate::actions;
+use crate::benchmark::Benchmark;
+use crate::expandable::{include, parallel, random_choice, transaction};
+use crate::tags::Tags;
+
+pub fn is_that_you(item: &Value) -> bool {
+  actions::Block::is_that_you(item)
+}
+
+pub fn expand(parent_path: &str, item: &Value, benchmark: &mut Benchmark, tags: &Tags) {
+  let items = expand_section(parent_path, item, "block", tags);
+  let rescue = expand_section(parent_path, item, "rescue", tags);
+  let always = expand_section(parent_path, item, "always", tags);
+
+  if items.is_empty() && always.is_empty() {
+    return;
+  }
+
+  benchmark.push(Box::new(actions::Block::new(item, items, rescue, always)));
+}
+
+fn expand_section(parent_path: &str, item: &Value, section: &str, tags: &Tags) -> Benchmark {
+  let mut benchmark: Benchmark = Benchmark::new();
+
+  if let Some(children) = item.get(section) {
+    let children = children.as_sequence().unwrap_or_else(|| panic!("`{section}` needs to be a list of items"));
+    let children: Vec<Value> = children.iter().map(|child| inherit(item, child)).collect();
+
+    include::expand_items(parent_path, &children, &mut benchmark, tags);
+  }
+
+  benchmark
+}
+
+// Groups of items pass their own headers to their children
+fn is_group(item: &Value) -> bool {
+  is_that_you(item) || transaction::is_that_you(item) || parallel::is_that_you(item) || random_choice::is_that_you(item)
+}
+
+// Children inherit the tags and headers of the block, so they are filtered and sent with them
+pub fn inherit(block: &Value, child: &Value) -> Value {
+  let mut child = child.clone();
+
+  if let Some(block_tags) = block.get("tags").and_then(|v| v.as_sequence()) {
+    if let Some(mapping) = child.as_mapping_mut() {
+      let child_tags = mapping.entry(Value::String("tags".into())).or_insert_with(|| Value::Sequence(Vec::new()));
+
+      if let Some(child_tags) = child_tags.as_sequence_mut() {
+        child_tags.extend(block_tags.iter().cloned());
+      }
+    }
+  }
+
+  if let Some(block_headers) = block.get("headers").and_then(|v| v.as_mapping()) {
+    let child_headers = if is_group(&child) {
+      child.as_mapping_mut()
+    } else {
+      child.get_mut("request").and_then(|v| v.as_mapping_mut())
+    };
+
+    if let Some(mapping) = child_headers {
+      let child_headers = mapping.entry(Value::String("headers".into())).or_insert_with(|| Value::Mapping(Mapping::new()));
+
+      if let Some(child_headers) = child_headers.as_mapping_mut() {
+        for (key, value) in block_headers.iter() {
+          if !child_headers.contains_key(key) {
+            child_headers.insert(key.clone(), value.clone());
+          }
+        }
+      }
+    }
+  }
+
+  // Loops aren't groups, so the items they repeat inherit from the block directly
+  if let Some(items) = child.get_mut("loop").and_then(|v| v.as_sequence_mut()) {
+    for item in items.iter_mut() {
+      *item = inherit(block, item);
+    }
+  }
+
+  child
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn expand_block() {
+    let text = "---\nname: Authenticated\nblock:\n  - name: Login\n    request:\n      url: /login\n  - name: Include comment\n    include: comments.yml\nrescue:\n  - name: Report\n    request:\n      url: /report\nalways:\n  - name: Logout\n    request:\n      url: /logout";
+    let docs = crate::reader::read_file_as_yml_from_str(text);
+    let doc = &docs[0];
+    let mut benchmark: Benchmark = Benchmark::new();
+
+    expand("example/benchmark.yml", doc, &mut benchmark, &Tags::new(None, None));
+
+    assert!(is_that_you(doc));
+    assert_eq!(benchmark.len(), 1);
+  }
+
+  #[test]
+  fn expand_block_filtered_by_tags() {
+    let text = "---\nname: Authenticated\nblock:\n  - name: Login\n    request:\n      url: /login\ntags:\n  - auth";
+    let docs = crate::reader::read_file_as_yml_from_str(text);
+    let doc = &docs[0];
+
+    let mut benchmark: Benchmark = Benchmark::new();
+    expand("example/benchmark.yml", doc, &mut benchmark, &Tags::new(Some("auth"), None));
+    assert_eq!(benchmark.len(), 1);
+
+    let mut benchmark: Benchmark = Benchmark::new();
+    expand("example/benchmark.yml", doc, &mut benchmark, &Tags::new(None, Some("auth")));
+    assert_eq!(benchmark.len(), 0);
+  }
+
+  #[test]
+  fn inherit_tags_and_headers() {
+    let text = "---\nname: Authenticated\nblock: []\ntags:\n  - auth\nheaders:\n  Authorization: Bearer {{ token }}\n  X-Foo: Bar";
+    let block = &crate::reader::read_file_as_yml_from_str(text)[0];
+    let child = &crate::reader::read_file_as_yml_from_str("---\nname: Login\nrequest:\n  url: /login\n  headers:\n    X-Foo: Baz\ntags:\n  - login")[0];
+
+    let child = inherit(block, child);
+
+    assert_eq!(serde_yaml::to_string(child.get("tags").unwrap()).unwrap(), "- login\n- auth\n");
+    assert_eq!(child.get("request").unwrap().get("headers").unwrap().get("Authorization").unwrap().as_str(), Some("Bearer {{ token }}"));
+    assert_eq!(child.get("request").unwrap().get("headers").unwrap().get("X-Foo").unwrap().as_str(), Some("Baz"));
+  }
+
+  #[test]
+  fn inherit_headers_through_groups_and_loops() {
+    let block = &crate::reader::read_file_as_yml_from_str("---\nname: Authenticated\nblock: []\nheaders:\n  Authorization: Bearer {{ token }}")[0];
+    let header = |item: &Value| item.get("headers").and_then(|v| v.get("Authorization")).and_then(|v| v.as_str()).map(str::to_string);
+
+    for group in ["transaction", "parallel"] {
+      let text = format!("---\nname: Checkout\n{group}:\n  - name: Pay\n    request:\n      url: /pay");
+      let child = inherit(block, &crate::reader::read_file_as_yml_from_str(&text)[0]);
+
+      assert_eq!(header(&child), Some("Bearer {{ token }}".to_string()));
+
+      // Groups pass them to their own children when they're expanded
+      let grandchild = inherit(&child, &child.get(group).unwrap()[0]);
+      assert_eq!(header(grandchild.get("request").unwrap()), Some("Bearer {{ token }}".to_string()));
+    }
+
+    let text = "---\nname: Browse\nrandom_choice:\n  - weight: 1\n    plan:\n      - name: Home\n        request:\n          url: /";
+    let child = inherit(block, &crate::reader::read_file_as_yml_from_str(text)[0]);
+    assert_eq!(header(&child), Some("Bearer {{ token }}".to_string()));
+
+    let text = "---\nname: Poll\nloop:\n  - name: Status\n    request:\n      url: /status\nuntil: iteration > 1";
+    let child = inherit(block, &crate::reader::read_file_as_yml_from_str(text)[0]);
+    assert_eq!(header(child.get("loop").unwrap()[0].get("request").unwrap()), Some("Bearer {{ token }}".to_string()));
+
+    let text = "---\nname: Fetch\nrequest:\n  url: /users/{{ item }}\nwith_items: [1, 2]";
+    let child = inherit(block, &crate::reader::read_file_as_yml_from_str(text)[0]);
+    assert_eq!(header(child.get("request").unwrap()), Some("Bearer {{ token }}".to_string()));
+  }
+}
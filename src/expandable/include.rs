@@ -1,11 +1,10 @@
 use serde_yaml::Value;
 use std::path::Path;
 
-use crate::interpolator::INTERPOLATION_REGEX;
-
 use crate::actions;
 use crate::benchmark::Benchmark;
-use crate::expandable::{include, multi_csv_request, multi_file_request, multi_iter_request, multi_request};
+use crate::expandable::{block, include, multi_csv_request, multi_file_request, multi_iter_request, multi_request, parallel, random_choice, transaction};
+use crate::interpolator;
 use crate::tags::Tags;
 
 use crate::reader;
@@ -15,11 +14,7 @@ pub fn is_that_you(item: &Value) -> bool {
 }
 
 pub fn expand(parent_path: &str, item: &Value, benchmark: &mut Benchmark, tags: &Tags) {
-  let include_path = item.get("include").and_then(|v| v.as_str()).unwrap();
-
-  if INTERPOLATION_REGEX.is_match(include_path) {
-    panic!("Interpolations not supported in 'include' property!");
-  }
+  let include_path = interpolator::resolve_on_load(item.get("include").and_then(|v| v.as_str()).unwrap());
 
   let include_filepath = Path::new(parent_path).with_file_name(include_path);
   let final_path = include_filepath.to_str().unwrap();
@@ -31,46 +26,113 @@ pub fn expand_from_filepath(parent_path: &str, benchmark: &mut Benchmark, access
   let docs = reader::read_file_as_yml(parent_path);
   let items = reader::read_yaml_doc_accessor(&docs[0], accessor);
 
+  expand_items(parent_path, items, benchmark, tags);
+}
+
+pub fn expand_items(parent_path: &str, items: &[Value], benchmark: &mut Benchmark, tags: &Tags) {
   for item in items {
-    if include::is_that_you(item) {
-      include::expand(parent_path, item, benchmark, tags);
+    if actions::When::is_that_you(item) {
+      let mut conditional: Benchmark = Benchmark::new();
 
-      continue;
+      expand_loop(parent_path, item, &mut conditional, tags);
+
+      if !conditional.is_empty() {
+        benchmark.push(Box::new(actions::When::new(item, conditional)));
+      }
+    } else {
+      expand_loop(parent_path, item, benchmark, tags);
     }
+  }
+}
+
+fn expand_loop(parent_path: &str, item: &Value, benchmark: &mut Benchmark, tags: &Tags) {
+  if actions::Until::is_that_you(item) {
+    let mut looped: Benchmark = Benchmark::new();
 
-    if tags.should_skip_item(item) {
-      continue;
+    match item.get("loop").and_then(|v| v.as_sequence()) {
+      // Looped items inherit the tags of the loop, so they are filtered with them like block children
+      Some(items) => {
+        let items: Vec<Value> = items.iter().map(|child| block::inherit(item, child)).collect();
+
+        expand_items(parent_path, &items, &mut looped, tags)
+      }
+      None => expand_item(parent_path, item, &mut looped, tags),
     }
 
-    if multi_request::is_that_you(item) {
-      multi_request::expand(item, benchmark);
-    } else if multi_iter_request::is_that_you(item) {
-      multi_iter_request::expand(item, benchmark);
-    } else if multi_csv_request::is_that_you(item) {
-      multi_csv_request::expand(parent_path, item, benchmark);
-    } else if multi_file_request::is_that_you(item) {
-      multi_file_request::expand(parent_path, item, benchmark);
-    } else if actions::Delay::is_that_you(item) {
-      benchmark.push(Box::new(actions::Delay::new(item, None)));
-    } else if actions::Exec::is_that_you(item) {
-      benchmark.push(Box::new(actions::Exec::new(item, None)));
-    } else if actions::Assign::is_that_you(item) {
-      benchmark.push(Box::new(actions::Assign::new(item, None)));
-    } else if actions::Assert::is_that_you(item) {
-      benchmark.push(Box::new(actions::Assert::new(item, None)));
-    } else if actions::Request::is_that_you(item) {
-      benchmark.push(Box::new(actions::Request::new(item, None, None)));
-    } else {
-      let out_str = serde_yaml::to_string(item).unwrap();
-      panic!("Unknown node:\n\n{out_str}\n\n");
+    if !looped.is_empty() {
+      benchmark.push(Box::new(actions::Until::new(item, looped)));
     }
+  } else {
+    expand_item(parent_path, item, benchmark, tags);
+  }
+}
+
+fn expand_item(parent_path: &str, item: &Value, benchmark: &mut Benchmark, tags: &Tags) {
+  if include::is_that_you(item) {
+    include::expand(parent_path, item, benchmark, tags);
+
+    return;
+  }
+
+  // Groups of items like blocks or transactions are filtered through the tags inherited by their children
+  if block::is_that_you(item) {
+    block::expand(parent_path, item, benchmark, tags);
+
+    return;
+  }
+
+  if transaction::is_that_you(item) {
+    transaction::expand(parent_path, item, benchmark, tags);
+
+    return;
+  }
+
+  if parallel::is_that_you(item) {
+    parallel::expand(parent_path, item, benchmark, tags);
+
+    return;
+  }
+
+  if random_choice::is_that_you(item) {
+    random_choice::expand(parent_path, item, benchmark, tags);
+
+    return;
+  }
+
+  if tags.should_skip_item(item) {
+    return;
+  }
+
+  if multi_request::is_that_you(item) {
+    multi_request::expand(parent_path, item, benchmark);
+  } else if multi_iter_request::is_that_you(item) {
+    multi_iter_request::expand(parent_path, item, benchmark);
+  } else if multi_csv_request::is_that_you(item) {
+    multi_csv_request::expand(parent_path, item, benchmark);
+  } else if multi_file_request::is_that_you(item) {
+    multi_file_request::expand(parent_path, item, benchmark);
+  } else if actions::Delay::is_that_you(item) {
+    benchmark.push(Box::new(actions::Delay::new(item, None)));
+  } else if actions::Exec::is_that_you(item) {
+    benchmark.push(Box::new(actions::Exec::new(item, None)));
+  } else if actions::Shared::is_that_you(item) {
+    benchmark.push(Box::new(actions::Shared::new(item)));
+  } else if actions::Assign::is_that_you(item) {
+    benchmark.push(Box::new(actions::Assign::new(item, None)));
+  } else if actions::Assert::is_that_you(item) {
+    benchmark.push(Box::new(actions::Assert::new(item, None)));
+  } else if actions::Request::is_that_you(item) {
+    benchmark.push(Box::new(actions::Request::new(parent_path, item, None, None)));
+  } else {
+    let out_str = serde_yaml::to_string(item).unwrap();
+    panic!("Unknown node:\n\n{out_str}\n\n");
   }
 }
 
 #[cfg(test)]
 mod tests {
   use crate::benchmark::Benchmark;
-  use crate::expandable::include::{expand, is_that_you};
+  use crate::expandable::include::{expand, expand_items, is_that_you};
   use crate::tags::Tags;
 
   #[test]
@@ -86,6 +148,80 @@ mod tests {
     assert_eq!(benchmark.len(), 2);
   }
 
+  #[test]
+  fn expand_when() {
+    let text = "---\n- name: Include comment\n  include: comments.yml\n  when: iteration % 2 == 0\n- name: Fetch users\n  request:\n    url: /api/users.json";
+    let docs = crate::reader::read_file_as_yml_from_str(text);
+    let mut benchmark: Benchmark = Benchmark::new();
+
+    expand_items("example/benchmark.yml", docs[0].as_sequence().unwrap(), &mut benchmark, &Tags::new(None, None));
+
+    assert_eq!(benchmark.len(), 2);
+  }
+
+  #[test]
+  fn expand_until() {
+    let text = "---\n- name: Poll comments\n  loop:\n    - name: Include comment\n      include: comments.yml\n  until: iteration > 1\n  retries: 2\n  delay: 100ms\n- name: Fetch users\n  request:\n    url: /api/users.json\n  until: iteration > 1";
+    let docs = crate::reader::read_file_as_yml_from_str(text);
+    let mut benchmark: Benchmark = Benchmark::new();
+
+    expand_items("example/benchmark.yml", docs[0].as_sequence().unwrap(), &mut benchmark, &Tags::new(None, None));
+
+    assert_eq!(benchmark.len(), 2);
+  }
+
+  #[test]
+  fn expand_until_with_tags() {
+    let text = "---\n- name: Poll comments\n  loop:\n    - name: Fetch comments\n      request:\n        url: /api/comments.json\n    - name: Fetch users\n      request:\n        url: /api/users.json\n      tags:\n        - users\n  until: iteration > 1\n  tags:\n    - polling";
+    let docs = crate::reader::read_file_as_yml_from_str(text);
+    let items = docs[0].as_sequence().unwrap();
+
+    let mut benchmark: Benchmark = Benchmark::new();
+    expand_items("example/benchmark.yml", items, &mut benchmark, &Tags::new(None, Some("polling")));
+    assert_eq!(benchmark.len(), 0);
+
+    let mut benchmark: Benchmark = Benchmark::new();
+    expand_items("example/benchmark.yml", items, &mut benchmark, &Tags::new(Some("polling"), None));
+    assert_eq!(benchmark.len(), 1);
+
+    let mut benchmark: Benchmark = Benchmark::new();
+    expand_items("example/benchmark.yml", items, &mut benchmark, &Tags::new(None, Some("users")));
+    assert_eq!(benchmark.len(), 1);
+  }
+
+  #[test]
+  #[should_panic(expected = "Invalid `when` expression")]
+  fn invalid_when() {
+    let text = "---\n- name: Fetch users\n  request:\n    url: /api/users.json\n  when: iteration ==";
+    let docs = crate::reader::read_file_as_yml_from_str(text);
+    let mut benchmark: Benchmark = Benchmark::new();
+
+    expand_items("example/benchmark.yml", docs[0].as_sequence().unwrap(), &mut benchmark, &Tags::new(None, None));
+  }
+
+  #[test]
+  fn expand_include_with_interpolations() {
+    std::env::set_var("DRILL_TEST_INCLUDE", "comments");
+
+    let text = "---\nname: Include comment\ninclude: '{{ DRILL_TEST_INCLUDE }}.yml'";
+    let docs = crate::reader::read_file_as_yml_from_str(text);
+    let mut benchmark: Benchmark = Benchmark::new();
+
+    expand("example/benchmark.yml", &docs[0], &mut benchmark, &Tags::new(None, None));
+
+    assert_eq!(benchmark.len(), 2);
+  }
+
+  #[test]
+  #[should_panic(expected = "Unknown 'DRILL_TEST_MISSING_INCLUDE' variable!")]
+  fn expand_include_with_unknown_variables() {
+    let text = "---\nname: Include comment\ninclude: '{{ DRILL_TEST_MISSING_INCLUDE }}.yml'";
+    let docs = crate::reader::read_file_as_yml_from_str(text);
+    let mut benchmark: Benchmark = Benchmark::new();
+
+    expand("example/benchmark.yml", &docs[0], &mut benchmark, &Tags::new(None, None));
+  }
+
   #[test]
   #[should_panic]
   fn invalid_expand() {
@@ -1,9 +1,13 @@
 pub mod include;
 
+mod block;
 mod multi_csv_request;
 mod multi_file_request;
 mod multi_iter_request;
 mod multi_request;
+mod parallel;
+mod random_choice;
+mod transaction;
 
 use serde_yaml::Value;
 
@@ -1,7 +1,7 @@
 use super::pick;
 use crate::actions::Request;
 use crate::benchmark::Benchmark;
-use crate::interpolator::INTERPOLATION_REGEX;
+use crate::interpolator;
 use crate::reader;
 use rand::seq::SliceRandom;
 use rand::thread_rng;
@@ -19,9 +19,7 @@ pub fn expand(parent_path: &str, item: &Value, benchmark: &mut Benchmark) {
     unreachable!();
   };
 
-  if INTERPOLATION_REGEX.is_match(with_items_path) {
-    panic!("Interpolation not supported in 'with_items_from_file' property!");
-  }
+  let with_items_path = interpolator::resolve_on_load(with_items_path);
 
   let with_items_filepath = Path::new(parent_path).with_file_name(with_items_path);
   let final_path = with_items_filepath.to_str().unwrap();
@@ -39,7 +37,7 @@ pub fn expand(parent_path: &str, item: &Value, benchmark: &mut Benchmark) {
   for (index, with_item) in with_items_file.iter().take(pick).enumerate() {
     let index = index as u32;
 
-    benchmark.push(Box::new(Request::new(item, Some(with_item.clone()), Some(index))));
+    benchmark.push(Box::new(Request::new(parent_path, item, Some(with_item.clone()), Some(index))));
   }
 }
 
@@ -4,7 +4,7 @@ use rand::seq::SliceRandom;
 use rand::thread_rng;
 use serde_yaml::{Number, Value};
 
-use crate::interpolator::INTERPOLATION_REGEX;
+use crate::interpolator;
 
 use crate::actions::Request;
 use crate::benchmark::Benchmark;
@@ -13,7 +13,7 @@ pub fn is_that_you(item: &Value) -> bool {
   item.get("request").and_then(|v| v.as_mapping()).is_some() && item.get("with_items_range").and_then(|v| v.as_mapping()).is_some()
 }
 
-pub fn expand(item: &Value, benchmark: &mut Benchmark) {
+pub fn expand(parent_path: &str, item: &Value, benchmark: &mut Benchmark) {
   if let Some(with_iter_items) = item.get("with_items_range").and_then(|v| v.as_mapping()) {
     let lstart = Value::String("start".into());
     let lstep = Value::String("step".into());
@@ -24,25 +24,9 @@ pub fn expand(item: &Value, benchmark: &mut Benchmark) {
     let vstep = with_iter_items.get(&lstep).unwrap_or(&default_step);
     let vstop = with_iter_items.get(&lstop).expect("Stop property is mandatory");
 
-    let start: &str = vstart.as_str().unwrap_or("");
-    let step: &str = vstep.as_str().unwrap_or("");
-    let stop: &str = vstop.as_str().unwrap_or("");
-
-    if INTERPOLATION_REGEX.is_match(start) {
-      panic!("Interpolations not supported in 'start' property!");
-    }
-
-    if INTERPOLATION_REGEX.is_match(step) {
-      panic!("Interpolations not supported in 'step' property!");
-    }
-
-    if INTERPOLATION_REGEX.is_match(stop) {
-      panic!("Interpolations not supported in 'stop' property!");
-    }
-
-    let start: i64 = vstart.as_i64().expect("Start needs to be a number");
-    let step: i64 = vstep.as_i64().expect("Step needs to be a number");
-    let stop: i64 = vstop.as_i64().expect("Stop needs to be a number");
+    let start: i64 = read_number(vstart).expect("Start needs to be a number");
+    let step: i64 = read_number(vstep).expect("Step needs to be a number");
+    let stop: i64 = read_number(vstop).expect("Stop needs to be a number");
 
     let stop = stop + 1; // making stop inclusive
 
@@ -63,12 +47,20 @@ pub fn expand(item: &Value, benchmark: &mut Benchmark) {
       for (index, value) in with_items.iter().enumerate() {
         let index = index as u32;
 
-        benchmark.push(Box::new(Request::new(item, Some(Value::Number(Number::from(*value))), Some(index))));
+        benchmark.push(Box::new(Request::new(parent_path, item, Some(Value::Number(Number::from(*value))), Some(index))));
       }
     }
   }
 }
 
+// Numbers can be interpolated when the benchmark is loaded, like `stop: '{{ users }}'`
+fn read_number(value: &Value) -> Option<i64> {
+  match value.as_str() {
+    Some(value) => interpolator::resolve_on_load(value).trim().parse().ok(),
+    None => value.as_i64(),
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -80,7 +72,7 @@ mod tests {
     let doc = &docs[0];
     let mut benchmark: Benchmark = Benchmark::new();
 
-    expand(doc, &mut benchmark);
+    expand("example/benchmark.yml", doc, &mut benchmark);
 
     assert!(is_that_you(doc));
     assert_eq!(benchmark.len(), 10);
@@ -93,12 +85,26 @@ mod tests {
     let doc = &docs[0];
     let mut benchmark: Benchmark = Benchmark::new();
 
-    expand(doc, &mut benchmark);
+    expand("example/benchmark.yml", doc, &mut benchmark);
 
     assert!(is_that_you(doc));
     assert_eq!(benchmark.len(), 3);
   }
 
+  #[test]
+  fn expand_multi_range_with_interpolations() {
+    std::env::set_var("DRILL_TEST_RANGE_STOP", "8");
+
+    let text = "---\nname: foobar\nrequest:\n  url: /api/{{ item }}\nwith_items_range:\n  start: 2\n  step: 2\n  stop: '{{ DRILL_TEST_RANGE_STOP }}'";
+    let docs = crate::reader::read_file_as_yml_from_str(text);
+    let doc = &docs[0];
+    let mut benchmark: Benchmark = Benchmark::new();
+
+    expand("example/benchmark.yml", doc, &mut benchmark);
+
+    assert_eq!(benchmark.len(), 4);
+  }
+
   #[test]
   #[should_panic]
   fn invalid_expand() {
@@ -107,7 +113,7 @@ mod tests {
     let doc = &docs[0];
     let mut benchmark: Benchmark = Benchmark::new();
 
-    expand(doc, &mut benchmark);
+    expand("example/benchmark.yml", doc, &mut benchmark);
   }
 
   #[test]
@@ -118,6 +124,6 @@ mod tests {
     let doc = &docs[0];
     let mut benchmark: Benchmark = Benchmark::new();
 
-    expand(doc, &mut benchmark);
+    expand("example/benchmark.yml", doc, &mut benchmark);
   }
 }
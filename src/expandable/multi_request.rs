@@ -5,13 +5,13 @@ use serde_yaml::Value;
 use super::pick;
 use crate::actions::Request;
 use crate::benchmark::Benchmark;
-use crate::interpolator::INTERPOLATION_REGEX;
+use crate::interpolator;
 
 pub fn is_that_you(item: &Value) -> bool {
   item.get("request").and_then(|v| v.as_mapping()).is_some() && item.get("with_items").and_then(|v| v.as_sequence()).is_some()
 }
 
-pub fn expand(item: &Value, benchmark: &mut Benchmark) {
+pub fn expand(parent_path: &str, item: &Value, benchmark: &mut Benchmark) {
   if let Some(with_items) = item.get("with_items").and_then(|v| v.as_sequence()) {
     let mut with_items_list = with_items.clone();
 
@@ -26,17 +26,19 @@ pub fn expand(item: &Value, benchmark: &mut Benchmark) {
     for (index, with_item) in with_items_list.iter().take(pick).enumerate() {
       let index = index as u32;
 
-      let value: &str = with_item.as_str().unwrap_or("");
-
-      if INTERPOLATION_REGEX.is_match(value) {
-        panic!("Interpolations not supported in 'with_items' children!");
-      }
-
-      benchmark.push(Box::new(Request::new(item, Some(with_item.clone()), Some(index))));
+      benchmark.push(Box::new(Request::new(parent_path, item, Some(read_item(with_item)), Some(index))));
     }
   }
 }
 
+// String items can be interpolated when the benchmark is loaded, like `- '{{ user_id }}'`
+fn read_item(value: &Value) -> Value {
+  match value.as_str() {
+    Some(value) => Value::String(interpolator::resolve_on_load(value)),
+    None => value.clone(),
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -48,7 +50,7 @@ mod tests {
     let doc = &docs[0];
     let mut benchmark: Benchmark = Benchmark::new();
 
-    expand(doc, &mut benchmark);
+    expand("example/benchmark.yml", doc, &mut benchmark);
 
     assert!(is_that_you(doc));
     assert_eq!(benchmark.len(), 3);
@@ -61,7 +63,7 @@ mod tests {
     let doc = &docs[0];
     let mut benchmark: Benchmark = Benchmark::new();
 
-    expand(doc, &mut benchmark);
+    expand("example/benchmark.yml", doc, &mut benchmark);
 
     assert!(is_that_you(doc));
     assert_eq!(benchmark.len(), 2);
@@ -74,20 +76,36 @@ mod tests {
     let doc = &docs[0];
     let mut benchmark: Benchmark = Benchmark::new();
 
-    expand(doc, &mut benchmark);
+    expand("example/benchmark.yml", doc, &mut benchmark);
 
     assert!(is_that_you(doc));
     assert_eq!(benchmark.len(), 1);
   }
 
   #[test]
-  #[should_panic]
+  fn expand_multi_with_interpolations() {
+    std::env::set_var("DRILL_TEST_ITEM", "bar");
+
+    let text = "---\nname: foobar\nrequest:\n  url: /api/{{ item }}\nwith_items:\n  - 1\n  - 2\n  - foo{{ DRILL_TEST_ITEM }}";
+    let docs = crate::reader::read_file_as_yml_from_str(text);
+    let doc = &docs[0];
+    let mut benchmark: Benchmark = Benchmark::new();
+
+    expand("example/benchmark.yml", doc, &mut benchmark);
+
+    assert_eq!(benchmark.len(), 3);
+    assert_eq!(read_item(&Value::from("foo{{ DRILL_TEST_ITEM }}")), Value::from("foobar"));
+    assert_eq!(read_item(&Value::from(1)), Value::from(1));
+  }
+
+  #[test]
+  #[should_panic(expected = "Unknown 'memory' variable!")]
   fn runtime_expand() {
     let text = "---\nname: foobar\nrequest:\n  url: /api/{{ item }}\nwith_items:\n  - 1\n  - 2\n  - foo{{ memory }}";
     let docs = crate::reader::read_file_as_yml_from_str(text);
     let doc = &docs[0];
     let mut benchmark: Benchmark = Benchmark::new();
 
-    expand(doc, &mut benchmark);
+    expand("example/benchmark.yml", doc, &mut benchmark);
   }
 }
@@ -0,0 +1,68 @@
+use serde_yaml::Value;
+
+use crate::actions;
+use crate::benchmark::Benchmark;
+use crate::expandable::{block, include};
+use crate::tags::Tags;
+
+const NWEIGHT: u64 = 1;
+
+pub fn is_that_you(item: &Value) -> bool {
+  actions::RandomChoice::is_that_you(item)
+}
+
+pub fn expand(parent_path: &str, item: &Value, benchmark: &mut Benchmark, tags: &Tags) {
+  let mut weights = Vec::new();
+  let mut branches = Vec::new();
+
+  for branch in item.get("random_choice").and_then(|v| v.as_sequence()).unwrap() {
+    let weight = match branch.get("weight") {
+      Some(weight) => weight.as_u64().expect("`weight` needs to be a positive number"),
+      None => NWEIGHT,
+    };
+
+    let children = branch.get("plan").and_then(|v| v.as_sequence()).expect("`random_choice` branches need a `plan` sequence");
+    let children: Vec<Value> = children.iter().map(|child| block::inherit(item, child)).collect();
+
+    // Branches left empty by the tags are kept, so the weights still apply
+    let mut items: Benchmark = Benchmark::new();
+    include::expand_items(parent_path, &children, &mut items, tags);
+
+    weights.push(weight);
+    branches.push(items);
+  }
+
+  if branches.iter().any(|items| !items.is_empty()) {
+    benchmark.push(Box::new(actions::RandomChoice::new(item, weights, branches)));
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn expand_random_choice() {
+    let text = "---\nname: Pick a path\nrandom_choice:\n  - weight: 3\n    plan:\n      - name: Browse\n        request:\n          url: /browse\n  - plan:\n      - name: Search\n        request:\n          url: /search";
+    let docs = crate::reader::read_file_as_yml_from_str(text);
+    let doc = &docs[0];
+
+    let mut benchmark: Benchmark = Benchmark::new();
+    expand("example/benchmark.yml", doc, &mut benchmark, &Tags::new(None, None));
+    assert!(is_that_you(doc));
+    assert_eq!(benchmark.len(), 1);
+
+    let mut benchmark: Benchmark = Benchmark::new();
+    expand("example/benchmark.yml", doc, &mut benchmark, &Tags::new(Some("other"), None));
+    assert_eq!(benchmark.len(), 0);
+  }
+
+  #[test]
+  #[should_panic]
+  fn invalid_branch() {
+    let text = "---\nname: Pick a path\nrandom_choice:\n  - weight: 3\n    request:\n      url: /browse";
+    let docs = crate::reader::read_file_as_yml_from_str(text);
+
+    expand("example/benchmark.yml", &docs[0], &mut Benchmark::new(), &Tags::new(None, None));
+  }
+}
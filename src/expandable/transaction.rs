@@ -0,0 +1,43 @@
+use serde_yaml::Value;
+
+use crate::actions;
+use crate::benchmark::Benchmark;
+use crate::expandable::{block, include};
+use crate::tags::Tags;
+
+pub fn is_that_you(item: &Value) -> bool {
+  actions::Transaction::is_that_you(item)
+}
+
+pub fn expand(parent_path: &str, item: &Value, benchmark: &mut Benchmark, tags: &Tags) {
+  let children = item.get("transaction").and_then(|v| v.as_sequence()).unwrap();
+  let children: Vec<Value> = children.iter().map(|child| block::inherit(item, child)).collect();
+
+  let mut items: Benchmark = Benchmark::new();
+  include::expand_items(parent_path, &children, &mut items, tags);
+
+  if !items.is_empty() {
+    benchmark.push(Box::new(actions::Transaction::new(item, items)));
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn expand_transaction() {
+    let text = "---\nname: Checkout\ntransaction:\n  - name: Add to cart\n    request:\n      url: /cart\n  - name: Wait\n    delay:\n      seconds: 1\n  - name: Pay\n    request:\n      url: /pay\ntags:\n  - checkout";
+    let docs = crate::reader::read_file_as_yml_from_str(text);
+    let doc = &docs[0];
+
+    let mut benchmark: Benchmark = Benchmark::new();
+    expand("example/benchmark.yml", doc, &mut benchmark, &Tags::new(Some("checkout"), None));
+    assert!(is_that_you(doc));
+    assert_eq!(benchmark.len(), 1);
+
+    let mut benchmark: Benchmark = Benchmark::new();
+    expand("example/benchmark.yml", doc, &mut benchmark, &Tags::new(None, Some("checkout")));
+    assert_eq!(benchmark.len(), 0);
+  }
+}
@@ -0,0 +1,112 @@
+use std::path::Path;
+use std::sync::Arc;
+
+use jsonschema::Validator;
+use serde_json::Value;
+use serde_yaml::Value as YamlValue;
+
+use crate::reader;
+
+#[derive(Clone)]
+pub struct Expectation {
+  schema: Arc<Validator>,
+}
+
+impl Expectation {
+  pub fn new(parent_path: &str, item: &YamlValue) -> Option<Expectation> {
+    let expect = item.get("expect")?;
+    let schema_path = expect.get("json_schema").and_then(|v| v.as_str()).expect("`expect` needs a `json_schema` file");
+
+    let schema_filepath = Path::new(parent_path).with_file_name(schema_path);
+    let final_path = schema_filepath.to_str().unwrap();
+
+    let content = reader::read_file(final_path);
+    let schema: Value = serde_json::from_str(&content).unwrap_or_else(|e| panic!("Invalid JSON in schema {final_path}: {e}"));
+    let validator = jsonschema::validator_for(&schema).unwrap_or_else(|e| panic!("Invalid JSON schema {final_path}: {e}"));
+
+    Some(Expectation {
+      schema: Arc::new(validator),
+    })
+  }
+
+  // Returns the first validation error found in the body, if any
+  pub fn validate(&self, data: &str) -> Option<String> {
+    let body: Value = match serde_json::from_str(data) {
+      Ok(body) => body,
+      Err(e) => return Some(format!("Invalid JSON body: {e}")),
+    };
+
+    self.schema.validate(&body).err().map(|error| {
+      let path = error.instance_path.to_string();
+      let path = if path.is_empty() {
+        "/".to_string()
+      } else {
+        path
+      };
+
+      format!("{path}: {error}")
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::io::Write;
+  use tempfile::NamedTempFile;
+
+  fn expectation(schema: &str) -> (NamedTempFile, Option<Expectation>) {
+    let mut schema_file = NamedTempFile::new().unwrap();
+    schema_file.write_all(schema.as_bytes()).unwrap();
+    schema_file.flush().unwrap();
+
+    let file_name = schema_file.path().file_name().unwrap().to_str().unwrap().to_string();
+    let parent_path = schema_file.path().with_file_name("benchmark.yml");
+    let text = format!("---\nname: foo\nrequest:\n  url: /\nexpect:\n  json_schema: {file_name}");
+    let item = &crate::reader::read_file_as_yml_from_str(&text)[0];
+
+    let expectation = Expectation::new(parent_path.to_str().unwrap(), item);
+
+    (schema_file, expectation)
+  }
+
+  const SCHEMA: &str = r#"{"type": "object", "required": ["items"], "properties": {"items": {"type": "array", "items": {"type": "object", "properties": {"id": {"type": "integer"}}}}}}"#;
+
+  #[test]
+  fn without_expectations() {
+    let item = &crate::reader::read_file_as_yml_from_str("---\nname: foo\nrequest:\n  url: /")[0];
+
+    assert!(Expectation::new("example/benchmark.yml", item).is_none());
+  }
+
+  #[test]
+  fn validates_a_valid_body() {
+    let (_file, expectation) = expectation(SCHEMA);
+
+    assert_eq!(expectation.unwrap().validate(r#"{"items": [{"id": 1}]}"#), None);
+  }
+
+  #[test]
+  fn reports_the_first_error_path() {
+    let (_file, expectation) = expectation(SCHEMA);
+    let error = expectation.unwrap().validate(r#"{"items": [{"id": 1}, {"id": "2"}]}"#).unwrap();
+
+    assert!(error.starts_with("/items/1/id: "), "{error}");
+  }
+
+  #[test]
+  fn reports_invalid_json_bodies() {
+    let (_file, expectation) = expectation(SCHEMA);
+    let error = expectation.unwrap().validate("<html></html>").unwrap();
+
+    assert!(error.starts_with("Invalid JSON body"), "{error}");
+  }
+
+  #[test]
+  #[should_panic(expected = "couldn't open")]
+  fn missing_schema_file() {
+    let item = &crate::reader::read_file_as_yml_from_str("---\nname: foo\nrequest:\n  url: /\nexpect:\n  json_schema: missing.json")[0];
+
+    Expectation::new("example/benchmark.yml", item);
+  }
+}
@@ -0,0 +1,473 @@
+use serde_json::{json, Value};
+
+use crate::functions;
+use crate::interpolator;
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+  Number(f64),
+  Str(String),
+  Ident(String),
+  Op(&'static str),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Operator {
+  Or,
+  And,
+  Eq,
+  Ne,
+  Lt,
+  Le,
+  Gt,
+  Ge,
+  Add,
+  Sub,
+  Mul,
+  Div,
+  Rem,
+  Concat,
+  Not,
+  Neg,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Expression {
+  Literal(Value),
+  Variable(String),
+  Unary(Operator, Box<Expression>),
+  Binary(Operator, Box<Expression>, Box<Expression>),
+  Conditional(Box<Expression>, Box<Expression>, Box<Expression>),
+  Call(String, Vec<Expression>),
+}
+
+static OPERATORS: [&str; 22] = ["{{", "}}", "==", "!=", "<=", ">=", "&&", "||", "<", ">", "+", "-", "*", "/", "%", "~", "!", "(", ")", "?", ":", ","];
+
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+  let chars: Vec<char> = input.chars().collect();
+  let mut tokens = Vec::new();
+  let mut i = 0;
+
+  while i < chars.len() {
+    let c = chars[i];
+
+    if c.is_whitespace() {
+      i += 1;
+    } else if c.is_ascii_digit() {
+      let start = i;
+      while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+        i += 1;
+      }
+      if i < chars.len() && (chars[i].is_alphabetic() || chars[i] == '_') {
+        return Err(format!("Unexpected character '{}' in number", chars[i]));
+      }
+      let number: String = chars[start..i].iter().collect();
+      tokens.push(Token::Number(number.parse().map_err(|_| format!("Invalid number '{number}'"))?));
+    } else if c == '\'' || c == '"' {
+      let mut value = String::new();
+      i += 1;
+      loop {
+        match chars.get(i) {
+          None => return Err("Unterminated string".to_string()),
+          Some('\\') => {
+            value.push(*chars.get(i + 1).ok_or("Unterminated string")?);
+            i += 2;
+          }
+          Some(ch) if *ch == c => {
+            i += 1;
+            break;
+          }
+          Some(ch) => {
+            value.push(*ch);
+            i += 1;
+          }
+        }
+      }
+      tokens.push(Token::Str(value));
+    } else if c.is_alphabetic() || c == '_' || c == '$' {
+      let start = i;
+      while i < chars.len() && (chars[i].is_alphanumeric() || "_$.".contains(chars[i])) {
+        i += 1;
+        // Array accessors are part of the variable path
+        while i < chars.len() && chars[i] == '[' {
+          while i < chars.len() && chars[i] != ']' {
+            i += 1;
+          }
+          i += 1;
+        }
+      }
+      let ident: String = chars[start..i.min(chars.len())].iter().collect();
+      tokens.push(match ident.as_str() {
+        "and" => Token::Op("&&"),
+        "or" => Token::Op("||"),
+        "not" => Token::Op("!"),
+        _ => Token::Ident(ident),
+      });
+    } else {
+      let rest: String = chars[i..].iter().take(2).collect();
+      match OPERATORS.iter().find(|op| rest.starts_with(*op)) {
+        Some(op) => {
+          tokens.push(Token::Op(op));
+          i += op.len();
+        }
+        None => return Err(format!("Unexpected character '{c}'")),
+      }
+    }
+  }
+
+  Ok(tokens)
+}
+
+struct Parser {
+  tokens: Vec<Token>,
+  position: usize,
+}
+
+impl Parser {
+  fn peek_op(&self) -> Option<&'static str> {
+    match self.tokens.get(self.position) {
+      Some(Token::Op(op)) => Some(op),
+      _ => None,
+    }
+  }
+
+  fn expect_op(&mut self, expected: &str) -> Result<(), String> {
+    match self.peek_op() {
+      Some(op) if op == expected => {
+        self.position += 1;
+        Ok(())
+      }
+      _ => Err(format!("Expected '{expected}'")),
+    }
+  }
+
+  fn conditional(&mut self) -> Result<Expression, String> {
+    let condition = self.or()?;
+
+    if self.peek_op() != Some("?") {
+      return Ok(condition);
+    }
+
+    self.position += 1;
+    let then = self.conditional()?;
+    self.expect_op(":")?;
+    let otherwise = self.conditional()?;
+
+    Ok(Expression::Conditional(Box::new(condition), Box::new(then), Box::new(otherwise)))
+  }
+
+  // Parses a chain of left associative binary operators of the same precedence
+  fn binary(&mut self, operators: &[(&str, Operator)], next: fn(&mut Parser) -> Result<Expression, String>) -> Result<Expression, String> {
+    let mut left = next(self)?;
+
+    while let Some(operator) = self.peek_op().and_then(|op| operators.iter().find(|(symbol, _)| *symbol == op)).map(|(_, operator)| *operator) {
+      self.position += 1;
+      let right = next(self)?;
+      left = Expression::Binary(operator, Box::new(left), Box::new(right));
+    }
+
+    Ok(left)
+  }
+
+  fn or(&mut self) -> Result<Expression, String> {
+    self.binary(&[("||", Operator::Or)], Parser::and)
+  }
+
+  fn and(&mut self) -> Result<Expression, String> {
+    self.binary(&[("&&", Operator::And)], Parser::equality)
+  }
+
+  fn equality(&mut self) -> Result<Expression, String> {
+    self.binary(&[("==", Operator::Eq), ("!=", Operator::Ne)], Parser::comparison)
+  }
+
+  fn comparison(&mut self) -> Result<Expression, String> {
+    self.binary(&[("<", Operator::Lt), ("<=", Operator::Le), (">", Operator::Gt), (">=", Operator::Ge)], Parser::additive)
+  }
+
+  fn additive(&mut self) -> Result<Expression, String> {
+    self.binary(&[("+", Operator::Add), ("-", Operator::Sub), ("~", Operator::Concat)], Parser::multiplicative)
+  }
+
+  fn multiplicative(&mut self) -> Result<Expression, String> {
+    self.binary(&[("*", Operator::Mul), ("/", Operator::Div), ("%", Operator::Rem)], Parser::unary)
+  }
+
+  fn unary(&mut self) -> Result<Expression, String> {
+    let operator = match self.peek_op() {
+      Some("!") => Operator::Not,
+      Some("-") => Operator::Neg,
+      _ => return self.primary(),
+    };
+
+    self.position += 1;
+
+    Ok(Expression::Unary(operator, Box::new(self.unary()?)))
+  }
+
+  fn primary(&mut self) -> Result<Expression, String> {
+    let token = self.tokens.get(self.position).cloned().ok_or("Unexpected end of expression")?;
+    self.position += 1;
+
+    match token {
+      Token::Number(n) => Ok(Expression::Literal(number(n))),
+      Token::Str(s) => Ok(Expression::Literal(json!(s))),
+      Token::Ident(ident) => Ok(match ident.as_str() {
+        "true" => Expression::Literal(json!(true)),
+        "false" => Expression::Literal(json!(false)),
+        "null" => Expression::Literal(Value::Null),
+        _ if self.peek_op() == Some("(") => self.call(ident)?,
+        _ => Expression::Variable(ident),
+      }),
+      // Interpolations inside expressions behave like parentheses, keeping the value types
+      Token::Op("(") => {
+        let expression = self.conditional()?;
+        self.expect_op(")")?;
+        Ok(expression)
+      }
+      Token::Op("{{") => {
+        let expression = self.conditional()?;
+        self.expect_op("}}")?;
+        Ok(expression)
+      }
+      Token::Op(op) => Err(format!("Unexpected '{op}'")),
+    }
+  }
+
+  fn call(&mut self, name: String) -> Result<Expression, String> {
+    let mut arguments = Vec::new();
+
+    self.expect_op("(")?;
+
+    if self.peek_op() != Some(")") {
+      arguments.push(self.conditional()?);
+
+      while self.peek_op() == Some(",") {
+        self.position += 1;
+        arguments.push(self.conditional()?);
+      }
+    }
+
+    self.expect_op(")")?;
+
+    Ok(Expression::Call(name, arguments))
+  }
+}
+
+impl Expression {
+  pub fn parse(input: &str) -> Result<Expression, String> {
+    let mut parser = Parser {
+      tokens: tokenize(input)?,
+      position: 0,
+    };
+
+    let expression = parser.conditional()?;
+
+    match parser.tokens.get(parser.position) {
+      None => Ok(expression),
+      Some(token) => Err(format!("Unexpected {token:?}")),
+    }
+  }
+
+  pub fn evaluate(&self, resolver: &dyn Fn(&str) -> Option<Value>) -> Result<Value, String> {
+    match self {
+      Expression::Literal(value) => Ok(value.clone()),
+      Expression::Variable(name) => resolver(name).ok_or_else(|| format!("Unknown '{name}' variable!")),
+      Expression::Unary(Operator::Not, expression) => Ok(json!(!is_truthy(&expression.evaluate(resolver)?))),
+      Expression::Unary(_, expression) => {
+        let value = expression.evaluate(resolver)?;
+        as_number(&value).map(|n| number(-n)).ok_or_else(|| format!("Cannot negate {value}"))
+      }
+      Expression::Binary(Operator::And, left, right) => Ok(json!(is_truthy(&left.evaluate(resolver)?) && is_truthy(&right.evaluate(resolver)?))),
+      Expression::Binary(Operator::Or, left, right) => Ok(json!(is_truthy(&left.evaluate(resolver)?) || is_truthy(&right.evaluate(resolver)?))),
+      Expression::Binary(operator, left, right) => binary(*operator, &left.evaluate(resolver)?, &right.evaluate(resolver)?),
+      Expression::Conditional(condition, then, otherwise) => {
+        if is_truthy(&condition.evaluate(resolver)?) {
+          then.evaluate(resolver)
+        } else {
+          otherwise.evaluate(resolver)
+        }
+      }
+      Expression::Call(name, arguments) => {
+        let arguments = arguments.iter().map(|argument| argument.evaluate(resolver)).collect::<Result<Vec<_>, _>>()?;
+
+        functions::call(name, &arguments)
+      }
+    }
+  }
+}
+
+fn binary(operator: Operator, left: &Value, right: &Value) -> Result<Value, String> {
+  let numbers = as_number(left).zip(as_number(right));
+
+  match operator {
+    Operator::Concat => Ok(json!(interpolator::stringify(left.clone()) + &interpolator::stringify(right.clone()))),
+    // Adding strings that aren't numbers concatenates them
+    Operator::Add if numbers.is_none() && (left.is_string() || right.is_string()) => binary(Operator::Concat, left, right),
+    Operator::Eq => Ok(json!(equals(left, right))),
+    Operator::Ne => Ok(json!(!equals(left, right))),
+    Operator::Lt | Operator::Le | Operator::Gt | Operator::Ge => {
+      let ordering = match (numbers, left, right) {
+        (Some((l, r)), _, _) => l.partial_cmp(&r),
+        (None, Value::String(l), Value::String(r)) => Some(l.cmp(r)),
+        _ => None,
+      }
+      .ok_or_else(|| format!("Cannot compare {left} and {right}"))?;
+
+      Ok(json!(match operator {
+        Operator::Lt => ordering.is_lt(),
+        Operator::Le => ordering.is_le(),
+        Operator::Gt => ordering.is_gt(),
+        _ => ordering.is_ge(),
+      }))
+    }
+    _ => {
+      let (l, r) = numbers.ok_or_else(|| format!("Cannot operate with {left} and {right}"))?;
+
+      match operator {
+        Operator::Add => Ok(number(l + r)),
+        Operator::Sub => Ok(number(l - r)),
+        Operator::Mul => Ok(number(l * r)),
+        Operator::Div if r == 0.0 => Err("Division by zero".to_string()),
+        Operator::Div => Ok(number(l / r)),
+        Operator::Rem if r == 0.0 => Err("Division by zero".to_string()),
+        _ => Ok(number(l % r)),
+      }
+    }
+  }
+}
+
+fn equals(left: &Value, right: &Value) -> bool {
+  match (as_number(left), as_number(right)) {
+    (Some(l), Some(r)) => l == r,
+    _ => left == right,
+  }
+}
+
+// Numeric strings, like the ones stored by `assign` or `exec`, can be used as numbers
+fn as_number(value: &Value) -> Option<f64> {
+  match value {
+    Value::Number(n) => n.as_f64(),
+    Value::String(s) => s.trim().parse().ok(),
+    _ => None,
+  }
+}
+
+fn number(n: f64) -> Value {
+  if n.fract() == 0.0 && n.abs() < i64::MAX as f64 {
+    json!(n as i64)
+  } else {
+    json!(n)
+  }
+}
+
+pub fn is_truthy(value: &Value) -> bool {
+  match value {
+    Value::Null => false,
+    Value::Bool(b) => *b,
+    Value::Number(n) => n.as_f64() != Some(0.0),
+    Value::String(s) => !s.is_empty() && s != "false",
+    Value::Array(a) => !a.is_empty(),
+    Value::Object(o) => !o.is_empty(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use serde_json::Map;
+
+  fn evaluate(input: &str) -> Result<Value, String> {
+    let mut context = Map::new();
+    context.insert("iteration".to_string(), json!("20"));
+    context.insert("login".to_string(), json!({"status": 200, "body": {"state": "done", "items": [{"id": 7}]}}));
+    let context = json!(context);
+
+    let resolver = |name: &str| context.pointer(&format!("/{}", name.replace(['.', '['], "/").replace(']', ""))).cloned();
+
+    Expression::parse(input)?.evaluate(&resolver)
+  }
+
+  #[test]
+  fn evaluates_arithmetic() {
+    assert_eq!(evaluate("1 + 2 * 3"), Ok(json!(7)));
+    assert_eq!(evaluate("(1 + 2) * 3"), Ok(json!(9)));
+    assert_eq!(evaluate("7 / 2"), Ok(json!(3.5)));
+    assert_eq!(evaluate("-3 + 1"), Ok(json!(-2)));
+    assert_eq!(evaluate("iteration % 10"), Ok(json!(0)));
+    assert_eq!(evaluate("1 / 0"), Err("Division by zero".to_string()));
+  }
+
+  #[test]
+  fn evaluates_comparisons() {
+    assert_eq!(evaluate("iteration % 10 == 0"), Ok(json!(true)));
+    assert_eq!(evaluate("login.status == 200"), Ok(json!(true)));
+    assert_eq!(evaluate("login.status != '200'"), Ok(json!(false)));
+    assert_eq!(evaluate("login.body.state == 'done'"), Ok(json!(true)));
+    assert_eq!(evaluate("login.body.items[0].id >= 7"), Ok(json!(true)));
+    assert_eq!(evaluate("'abc' < 'abd'"), Ok(json!(true)));
+  }
+
+  #[test]
+  fn evaluates_boolean_logic() {
+    assert_eq!(evaluate("true && !false"), Ok(json!(true)));
+    assert_eq!(evaluate("login.status == 200 and not (iteration > 30)"), Ok(json!(true)));
+    assert_eq!(evaluate("false or null"), Ok(json!(false)));
+  }
+
+  #[test]
+  fn evaluates_strings() {
+    assert_eq!(evaluate("'user-' + iteration"), Ok(json!("user-20")));
+    assert_eq!(evaluate("iteration + 1"), Ok(json!(21)));
+    assert_eq!(evaluate("iteration ~ 1"), Ok(json!("201")));
+    assert_eq!(evaluate("login.body.state + '!'"), Ok(json!("done!")));
+    assert!(evaluate("null + 1").is_err());
+  }
+
+  #[test]
+  fn evaluates_conditionals() {
+    assert_eq!(evaluate("login.status == 200 ? 'ok' : 'ko'"), Ok(json!("ok")));
+    assert_eq!(evaluate("iteration > 30 ? 'high' : iteration > 10 ? 'medium' : 'low'"), Ok(json!("medium")));
+    assert_eq!(evaluate("(false ? 1 : 2) * 10"), Ok(json!(20)));
+    assert!(Expression::parse("true ? 1").is_err());
+  }
+
+  #[test]
+  fn evaluates_functions() {
+    assert_eq!(evaluate("random_int(iteration, 20) * 2"), Ok(json!(40)));
+    assert_eq!(evaluate("random_item(login.body.items)"), Ok(json!({"id": 7})));
+    assert_eq!(evaluate("random_string(3) != ''"), Ok(json!(true)));
+    assert!(evaluate("unknown()").is_err());
+    assert!(Expression::parse("random_int(1,").is_err());
+  }
+
+  #[test]
+  fn evaluates_interpolations() {
+    assert_eq!(evaluate("{{ login.status }} == 200"), Ok(json!(true)));
+    assert_eq!(evaluate("{{ login.body.state }} == \"done\""), Ok(json!(true)));
+  }
+
+  #[test]
+  fn fails_on_unknown_variables() {
+    assert_eq!(evaluate("missing == 1"), Err("Unknown 'missing' variable!".to_string()));
+  }
+
+  #[test]
+  fn fails_on_invalid_syntax() {
+    assert!(Expression::parse("1 +").is_err());
+    assert!(Expression::parse("(1").is_err());
+    assert!(Expression::parse("1 2").is_err());
+    assert!(Expression::parse("'abc").is_err());
+    assert!(Expression::parse("5digitzip").is_err());
+  }
+
+  #[test]
+  fn truthiness() {
+    assert!(!is_truthy(&json!(null)));
+    assert!(!is_truthy(&json!(0)));
+    assert!(!is_truthy(&json!("")));
+    assert!(!is_truthy(&json!("false")));
+    assert!(is_truthy(&json!("foo")));
+    assert!(is_truthy(&json!([1])));
+  }
+}
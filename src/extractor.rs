@@ -0,0 +1,318 @@
+use regex::Regex;
+use scraper::{Html, Selector};
+use serde_json::{json, Map, Value};
+use serde_json_path::JsonPath;
+use serde_yaml::Value as YamlValue;
+use sxd_xpath::{Context as XPathContext, Factory};
+
+#[derive(Clone)]
+enum Source {
+  JsonPath(JsonPath),
+  Regex(Regex, Option<String>),
+  Header(String, Option<(Regex, Option<String>)>),
+  XPath(String, Vec<(String, String)>),
+  Css(Selector, Option<String>),
+}
+
+#[derive(Clone)]
+pub struct Extractor {
+  pub key: String,
+  source: Source,
+  all: bool,
+}
+
+impl Extractor {
+  pub fn new(key: &str, value: &YamlValue) -> Extractor {
+    let (source, all) = if let Some(expression) = value.as_str() {
+      (Source::JsonPath(parse_jsonpath(key, expression)), false)
+    } else if value.as_mapping().is_some() {
+      let all = value.get("all").and_then(|v| v.as_bool()).unwrap_or(false);
+      let regex = value.get("regex").and_then(|v| v.as_str()).map(|expression| (parse_regex(key, expression), extract_group(value)));
+
+      let source = if let Some(expression) = value.get("jsonpath").and_then(|v| v.as_str()) {
+        Source::JsonPath(parse_jsonpath(key, expression))
+      } else if let Some(header) = value.get("header").and_then(|v| v.as_str()) {
+        Source::Header(header.to_lowercase(), regex)
+      } else if let Some((regex, group)) = regex {
+        Source::Regex(regex, group)
+      } else if let Some(expression) = value.get("xpath").and_then(|v| v.as_str()) {
+        Source::XPath(parse_xpath(key, expression), extract_namespaces(value))
+      } else if let Some(selector) = value.get("css").and_then(|v| v.as_str()) {
+        let attribute = value.get("attribute").and_then(|v| v.as_str()).map(|v| v.to_string());
+
+        Source::Css(parse_selector(key, selector), attribute)
+      } else {
+        panic!("`{key}` extraction needs a `jsonpath`, `regex`, `header`, `xpath` or `css` property");
+      };
+
+      (source, all)
+    } else {
+      panic!("`{key}` extraction needs to be a JSONPath string or a mapping");
+    };
+
+    Extractor {
+      key: key.to_string(),
+      source,
+      all,
+    }
+  }
+
+  // Returns the first match, or all of them as an array when `all` is set
+  pub fn extract(&self, data: &str, body: &Value, headers: &Map<String, Value>) -> Value {
+    let matches: Vec<Value> = match &self.source {
+      Source::JsonPath(path) => path.query(body).all().into_iter().cloned().collect(),
+      Source::Regex(regex, group) => captures(regex, group, data),
+      Source::Header(name, regex) => match (headers.get(name).and_then(|v| v.as_str()), regex) {
+        (Some(value), Some((regex, group))) => captures(regex, group, value),
+        (Some(value), None) => vec![json!(value)],
+        (None, _) => Vec::new(),
+      },
+      Source::XPath(expression, namespaces) => xpath_matches(expression, namespaces, data),
+      Source::Css(selector, attribute) => css_matches(selector, attribute, data),
+    };
+
+    if self.all {
+      json!(matches)
+    } else {
+      matches.into_iter().next().unwrap_or(Value::Null)
+    }
+  }
+}
+
+fn parse_jsonpath(key: &str, expression: &str) -> JsonPath {
+  JsonPath::parse(expression).unwrap_or_else(|e| panic!("Invalid JSONPath expression for `{key}`: {e}"))
+}
+
+fn parse_regex(key: &str, expression: &str) -> Regex {
+  Regex::new(expression).unwrap_or_else(|e| panic!("Invalid regular expression for `{key}`: {e}"))
+}
+
+// XPath expressions aren't thread safe, so they are validated here and built again on every extraction
+fn parse_xpath(key: &str, expression: &str) -> String {
+  match Factory::new().build(expression) {
+    Ok(Some(_)) => expression.to_string(),
+    Ok(None) => panic!("Empty XPath expression for `{key}`"),
+    Err(e) => panic!("Invalid XPath expression for `{key}`: {e}"),
+  }
+}
+
+fn parse_selector(key: &str, selector: &str) -> Selector {
+  Selector::parse(selector).unwrap_or_else(|e| panic!("Invalid CSS selector for `{key}`: {e}"))
+}
+
+fn extract_namespaces(value: &YamlValue) -> Vec<(String, String)> {
+  match value.get("namespaces").and_then(|v| v.as_mapping()) {
+    Some(mapping) => mapping
+      .iter()
+      .map(|(prefix, uri)| {
+        let prefix = prefix.as_str().expect("Namespace prefixes must be strings");
+        let uri = uri.as_str().expect("Namespace URIs must be strings");
+
+        (prefix.to_string(), uri.to_string())
+      })
+      .collect(),
+    None => Vec::new(),
+  }
+}
+
+fn extract_group(value: &YamlValue) -> Option<String> {
+  match value.get("group") {
+    Some(YamlValue::Number(n)) => Some(n.to_string()),
+    Some(YamlValue::String(s)) => Some(s.to_owned()),
+    _ => None,
+  }
+}
+
+// Without an explicit group, the first capture group is used if the regex has any
+fn captures(regex: &Regex, group: &Option<String>, text: &str) -> Vec<Value> {
+  regex
+    .captures_iter(text)
+    .filter_map(|caps| {
+      let capture = match group {
+        Some(group) => match group.parse::<usize>() {
+          Ok(index) => caps.get(index),
+          Err(_) => caps.name(group),
+        },
+        None => caps.get(1).or_else(|| caps.get(0)),
+      };
+
+      capture.map(|m| json!(m.as_str()))
+    })
+    .collect()
+}
+
+fn xpath_matches(expression: &str, namespaces: &[(String, String)], data: &str) -> Vec<Value> {
+  let package = match sxd_document::parser::parse(data) {
+    Ok(package) => package,
+    Err(_) => return Vec::new(),
+  };
+  let document = package.as_document();
+
+  let mut context = XPathContext::new();
+  for (prefix, uri) in namespaces {
+    context.set_namespace(prefix, uri);
+  }
+
+  let xpath = Factory::new().build(expression).unwrap().unwrap();
+
+  match xpath.evaluate(&context, document.root()) {
+    Ok(sxd_xpath::Value::Nodeset(nodes)) => nodes.document_order().iter().map(|node| json!(node.string_value())).collect(),
+    Ok(sxd_xpath::Value::Boolean(value)) => vec![json!(value)],
+    Ok(sxd_xpath::Value::Number(value)) => vec![json!(value)],
+    Ok(sxd_xpath::Value::String(value)) => vec![json!(value)],
+    Err(_) => Vec::new(),
+  }
+}
+
+fn css_matches(selector: &Selector, attribute: &Option<String>, data: &str) -> Vec<Value> {
+  let document = Html::parse_document(data);
+
+  document
+    .select(selector)
+    .filter_map(|element| match attribute {
+      Some(attribute) => element.value().attr(attribute).map(|value| json!(value)),
+      None => Some(json!(element.text().collect::<String>().trim())),
+    })
+    .collect()
+}
+
+pub fn extract_extractors(item: &YamlValue) -> Vec<Extractor> {
+  match item.get("extract") {
+    Some(extract) => match extract.as_mapping() {
+      Some(mapping) => mapping
+        .iter()
+        .map(|(key, value)| {
+          let key = key.as_str().expect("Extraction keys must be strings");
+
+          Extractor::new(key, value)
+        })
+        .collect(),
+      None => panic!("`extract` needs to be a mapping of variable names and expressions"),
+    },
+    None => Vec::new(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn items() -> Value {
+    json!({
+      "items": [
+        {"id": 1, "status": "inactive"},
+        {"id": 2, "status": "active"},
+        {"id": 3, "status": "active"}
+      ]
+    })
+  }
+
+  fn headers() -> Map<String, Value> {
+    let mut headers = Map::new();
+    headers.insert("location".to_string(), json!("/api/users/42"));
+    headers.insert("x-request-id".to_string(), json!("abc-123"));
+    headers
+  }
+
+  fn extractors(text: &str) -> Vec<Extractor> {
+    let docs = crate::reader::read_file_as_yml_from_str(text);
+
+    extract_extractors(&docs[0])
+  }
+
+  fn extract_json(extractor: &Extractor, body: &Value) -> Value {
+    extractor.extract(&body.to_string(), body, &Map::new())
+  }
+
+  fn extract_text(extractor: &Extractor, data: &str) -> Value {
+    extractor.extract(data, &Value::Null, &headers())
+  }
+
+  #[test]
+  fn extracts_first_match_with_filters() {
+    let extractors = extractors("---\nname: foo\nrequest:\n  url: /\nextract:\n  user_id: \"$.items[?@.status == 'active'].id\"");
+
+    assert_eq!(extractors.len(), 1);
+    assert_eq!(extractors[0].key, "user_id");
+    assert_eq!(extract_json(&extractors[0], &items()), json!(2));
+  }
+
+  #[test]
+  fn extracts_all_matches() {
+    let extractors = extractors("---\nname: foo\nrequest:\n  url: /\nextract:\n  ids:\n    jsonpath: $..id\n    all: true");
+
+    assert_eq!(extract_json(&extractors[0], &items()), json!([1, 2, 3]));
+  }
+
+  #[test]
+  fn extracts_null_without_matches() {
+    let extractors = extractors("---\nname: foo\nrequest:\n  url: /\nextract:\n  missing: $.foo.bar");
+
+    assert_eq!(extract_json(&extractors[0], &items()), Value::Null);
+  }
+
+  #[test]
+  fn no_extractors() {
+    let extractors = extractors("---\nname: foo\nrequest:\n  url: /");
+
+    assert!(extractors.is_empty());
+  }
+
+  #[test]
+  #[should_panic(expected = "Invalid JSONPath expression")]
+  fn invalid_expression() {
+    extractors("---\nname: foo\nrequest:\n  url: /\nextract:\n  bad: $.items[?");
+  }
+
+  #[test]
+  fn extracts_regex_capture_groups() {
+    let extractors = extractors("---\nname: foo\nrequest:\n  url: /\nextract:\n  token:\n    regex: 'name=\"csrf\" value=\"([^\"]+)\"'\n  whole:\n    regex: 'value=\"[^\"]+\"'\n  named:\n    regex: 'id=(?P<id>\\d+)'\n    group: id\n    all: true");
+    let html = "<input name=\"csrf\" value=\"s3cr3t\"><a href=\"?id=1\"></a><a href=\"?id=2\"></a>";
+
+    assert_eq!(extract_text(&extractors[0], html), json!("s3cr3t"));
+    assert_eq!(extract_text(&extractors[1], html), json!("value=\"s3cr3t\""));
+    assert_eq!(extract_text(&extractors[2], html), json!(["1", "2"]));
+  }
+
+  #[test]
+  fn extracts_headers() {
+    let extractors = extractors("---\nname: foo\nrequest:\n  url: /\nextract:\n  request_id:\n    header: X-Request-Id\n  user_id:\n    header: Location\n    regex: /users/(\\d+)\n  missing:\n    header: X-Missing");
+
+    assert_eq!(extract_text(&extractors[0], ""), json!("abc-123"));
+    assert_eq!(extract_text(&extractors[1], ""), json!("42"));
+    assert_eq!(extract_text(&extractors[2], ""), Value::Null);
+  }
+
+  #[test]
+  fn extracts_xpath() {
+    let extractors = extractors("---\nname: foo\nrequest:\n  url: /\nextract:\n  token:\n    xpath: //soap:Body/m:LoginResponse/m:Token\n    namespaces:\n      soap: http://www.w3.org/2003/05/soap-envelope\n      m: http://example.com/login\n  ids:\n    xpath: //m:Id/text()\n    namespaces:\n      m: http://example.com/login\n    all: true\n  count:\n    xpath: count(//*)");
+    let xml = r#"<?xml version="1.0"?><soap:Envelope xmlns:soap="http://www.w3.org/2003/05/soap-envelope"><soap:Body><m:LoginResponse xmlns:m="http://example.com/login"><m:Token>abc</m:Token><m:Id>1</m:Id><m:Id>2</m:Id></m:LoginResponse></soap:Body></soap:Envelope>"#;
+
+    assert_eq!(extract_text(&extractors[0], xml), json!("abc"));
+    assert_eq!(extract_text(&extractors[1], xml), json!(["1", "2"]));
+    assert_eq!(extract_text(&extractors[2], xml), json!(6.0));
+    assert_eq!(extract_text(&extractors[0], "not xml"), Value::Null);
+  }
+
+  #[test]
+  fn extracts_css() {
+    let extractors = extractors("---\nname: foo\nrequest:\n  url: /\nextract:\n  csrf:\n    css: form input[name=csrf]\n    attribute: value\n  title:\n    css: h1\n  links:\n    css: a\n    attribute: href\n    all: true");
+    let html = r#"<html><body><h1> Login </h1><form><input type="hidden" name="csrf" value="t0k3n"></form><a href="/a">A</a><a href="/b">B</a></body></html>"#;
+
+    assert_eq!(extract_text(&extractors[0], html), json!("t0k3n"));
+    assert_eq!(extract_text(&extractors[1], html), json!("Login"));
+    assert_eq!(extract_text(&extractors[2], html), json!(["/a", "/b"]));
+  }
+
+  #[test]
+  #[should_panic(expected = "Invalid CSS selector")]
+  fn invalid_selector() {
+    extractors("---\nname: foo\nrequest:\n  url: /\nextract:\n  bad:\n    css: 'a >> b'");
+  }
+
+  #[test]
+  #[should_panic(expected = "Invalid regular expression")]
+  fn invalid_regex() {
+    extractors("---\nname: foo\nrequest:\n  url: /\nextract:\n  bad:\n    regex: '(unclosed'");
+  }
+}
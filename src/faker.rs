@@ -0,0 +1,171 @@
+use std::sync::Mutex;
+
+use fake::faker::{address, company, creditcard, internet, job, lorem, name, phone_number};
+use fake::locales::{Data, AR_SA, EN, FR_FR, JA_JP, PT_BR, ZH_CN, ZH_TW};
+use fake::Fake;
+use lazy_static::lazy_static;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use serde_yaml::Value;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Locale {
+  En,
+  FrFr,
+  PtBr,
+  JaJp,
+  ZhCn,
+  ZhTw,
+  ArSa,
+}
+
+impl Locale {
+  fn parse(locale: &str) -> Option<Locale> {
+    match locale.to_lowercase().replace('-', "_").as_str() {
+      "en" => Some(Locale::En),
+      "fr_fr" => Some(Locale::FrFr),
+      "pt_br" => Some(Locale::PtBr),
+      "ja_jp" => Some(Locale::JaJp),
+      "zh_cn" => Some(Locale::ZhCn),
+      "zh_tw" => Some(Locale::ZhTw),
+      "ar_sa" => Some(Locale::ArSa),
+      _ => None,
+    }
+  }
+}
+
+struct Faker {
+  locale: Locale,
+  rng: StdRng,
+}
+
+lazy_static! {
+  // A single generator, so seeded benchmarks generate the same sequence of values
+  static ref FAKER: Mutex<Faker> = Mutex::new(Faker {
+    locale: Locale::En,
+    rng: StdRng::from_entropy(),
+  });
+}
+
+#[cfg(test)]
+lazy_static! {
+  // Serializes the tests using the generator, so seeded sequences aren't consumed by other tests
+  pub static ref TEST_LOCK: Mutex<()> = Mutex::new(());
+}
+
+// Reads the `faker` benchmark settings, like `faker: { locale: fr_fr, seed: 42 }`
+pub fn configure(doc: &Value) {
+  let Some(settings) = doc.get("faker") else {
+    return;
+  };
+
+  let mut faker = FAKER.lock().unwrap();
+
+  if let Some(locale) = settings.get("locale") {
+    let locale = locale.as_str().expect("`locale` needs to be a string");
+
+    faker.locale = Locale::parse(locale).unwrap_or_else(|| panic!("Unknown '{locale}' faker locale! Use en, fr_fr, pt_br, ja_jp, zh_cn, zh_tw or ar_sa"));
+  }
+
+  if let Some(seed) = settings.get("seed") {
+    faker.rng = StdRng::seed_from_u64(seed.as_u64().expect("`seed` needs to be a positive number"));
+  }
+}
+
+// Generates a fake value, like `email`, optionally for another locale, like `fr_fr.name`
+pub fn fake(path: &str) -> Option<String> {
+  let mut faker = FAKER.lock().unwrap();
+
+  let (locale, name) = match path.split_once('.') {
+    Some((locale, name)) => (Locale::parse(locale)?, name),
+    None => (faker.locale, path),
+  };
+
+  let rng = &mut faker.rng;
+
+  match locale {
+    Locale::En => generate(EN, name, rng),
+    Locale::FrFr => generate(FR_FR, name, rng),
+    Locale::PtBr => generate(PT_BR, name, rng),
+    Locale::JaJp => generate(JA_JP, name, rng),
+    Locale::ZhCn => generate(ZH_CN, name, rng),
+    Locale::ZhTw => generate(ZH_TW, name, rng),
+    Locale::ArSa => generate(AR_SA, name, rng),
+  }
+}
+
+fn generate<L: Data + Copy>(locale: L, name: &str, rng: &mut StdRng) -> Option<String> {
+  let value = match name {
+    "name" => name::raw::Name(locale).fake_with_rng(rng),
+    "first_name" => name::raw::FirstName(locale).fake_with_rng(rng),
+    "last_name" => name::raw::LastName(locale).fake_with_rng(rng),
+    "email" => internet::raw::SafeEmail(locale).fake_with_rng(rng),
+    "free_email" => internet::raw::FreeEmail(locale).fake_with_rng(rng),
+    "username" => internet::raw::Username(locale).fake_with_rng(rng),
+    "password" => internet::raw::Password(locale, 10..20).fake_with_rng(rng),
+    "ipv4" => internet::raw::IPv4(locale).fake_with_rng(rng),
+    "user_agent" => internet::raw::UserAgent(locale).fake_with_rng(rng),
+    "phone" => phone_number::raw::PhoneNumber(locale).fake_with_rng(rng),
+    "cell_phone" => phone_number::raw::CellNumber(locale).fake_with_rng(rng),
+    "street" => address::raw::StreetName(locale).fake_with_rng(rng),
+    "building_number" => address::raw::BuildingNumber(locale).fake_with_rng(rng),
+    "city" => address::raw::CityName(locale).fake_with_rng(rng),
+    "zip" => address::raw::ZipCode(locale).fake_with_rng(rng),
+    "state" => address::raw::StateName(locale).fake_with_rng(rng),
+    "country" => address::raw::CountryName(locale).fake_with_rng(rng),
+    "country_code" => address::raw::CountryCode(locale).fake_with_rng(rng),
+    "company" => company::raw::CompanyName(locale).fake_with_rng(rng),
+    "job" => job::raw::Title(locale).fake_with_rng(rng),
+    "credit_card" => creditcard::raw::CreditCardNumber(locale).fake_with_rng(rng),
+    "word" => lorem::raw::Word(locale).fake_with_rng(rng),
+    "sentence" => lorem::raw::Sentence(locale, 4..10).fake_with_rng(rng),
+    "paragraph" => lorem::raw::Paragraph(locale, 3..6).fake_with_rng(rng),
+    _ => return None,
+  };
+
+  Some(value)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn generates_fakes() {
+    let _lock = TEST_LOCK.lock().unwrap();
+
+    assert!(fake("email").unwrap().contains('@'));
+    assert!(!fake("name").unwrap().is_empty());
+    assert!(fake("credit_card").unwrap().chars().all(|c| c.is_ascii_digit()));
+    assert!(!fake("fr_fr.city").unwrap().is_empty());
+    assert!(!fake("ja_jp.name").unwrap().is_ascii());
+    assert_eq!(fake("unknown"), None);
+    assert_eq!(fake("xx_xx.name"), None);
+  }
+
+  #[test]
+  fn generates_seeded_fakes() {
+    let _lock = TEST_LOCK.lock().unwrap();
+
+    let doc = &crate::reader::read_file_as_yml_from_str("---\nfaker:\n  seed: 42")[0];
+    let context = crate::benchmark::Context::new();
+    let interpolator = crate::interpolator::Interpolator::new(&context);
+    let template = "{{ fake.email }} {{ fake.name }} {{ fake.fr_fr.phone }}";
+
+    configure(doc);
+    let first: Vec<String> = (0..3).map(|_| interpolator.resolve(template, true)).collect();
+
+    configure(doc);
+    let second: Vec<String> = (0..3).map(|_| interpolator.resolve(template, true)).collect();
+
+    assert_eq!(first, second);
+    assert_ne!(first[0], first[1]);
+  }
+
+  #[test]
+  fn parses_locales() {
+    assert_eq!(Locale::parse("pt-BR"), Some(Locale::PtBr));
+    assert_eq!(Locale::parse("en"), Some(Locale::En));
+    assert_eq!(Locale::parse("es_es"), None);
+  }
+}
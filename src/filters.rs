@@ -0,0 +1,146 @@
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use hmac::{Hmac, Mac};
+use lazy_static::lazy_static;
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use regex::Regex;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+use crate::interpolator;
+
+// Pipe filters following a variable in an interpolation, like `{{ name | upper | default('x') }}`
+pub static FILTERS_PATTERN: &str = r#"((?:\| *[a-z0-9_]+ *(?:\( *(?:"[^"]*"|'[^']*') *\))? *)*)"#;
+
+// Unreserved characters, like in RFC 3986
+pub const URL_COMPONENT: &AsciiSet = &NON_ALPHANUMERIC.remove(b'-').remove(b'_').remove(b'.').remove(b'~');
+
+lazy_static! {
+  static ref FILTER_REGEX: Regex = Regex::new(r#"\| *([a-z0-9_]+) *(?:\( *(?:"([^"]*)"|'([^']*)') *\))? *"#).unwrap();
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Filter {
+  UrlEncode,
+  Base64,
+  Base64Decode,
+  Json,
+  Upper,
+  Lower,
+  Trim,
+  Sha256,
+  Hmac(String),
+  Default(String),
+}
+
+impl Filter {
+  pub fn parse(source: &str) -> Result<Vec<Filter>, String> {
+    FILTER_REGEX
+      .captures_iter(source)
+      .map(|caps| {
+        let name = &caps[1];
+        let argument = caps.get(2).or_else(|| caps.get(3)).map(|m| m.as_str().to_string());
+
+        match (name, argument) {
+          ("urlencode", None) => Ok(Filter::UrlEncode),
+          ("base64", None) => Ok(Filter::Base64),
+          ("base64decode", None) => Ok(Filter::Base64Decode),
+          ("json", None) => Ok(Filter::Json),
+          ("upper", None) => Ok(Filter::Upper),
+          ("lower", None) => Ok(Filter::Lower),
+          ("trim", None) => Ok(Filter::Trim),
+          ("sha256", None) => Ok(Filter::Sha256),
+          ("hmac", Some(key)) => Ok(Filter::Hmac(key)),
+          ("default", Some(value)) => Ok(Filter::Default(value)),
+          ("hmac", None) | ("default", None) => Err(format!("The '{name}' filter needs an argument!")),
+          (_, _) => Err(format!("Unknown '{name}' filter!")),
+        }
+      })
+      .collect()
+  }
+
+  // Missing values are only handled by the default filter
+  pub fn apply(&self, value: Option<Value>) -> Result<Option<Value>, String> {
+    let value = match (self, value) {
+      (Filter::Default(default), None) => return Ok(Some(json!(default))),
+      (Filter::Default(default), Some(Value::Null)) => return Ok(Some(json!(default))),
+      (Filter::Default(default), Some(Value::String(s))) if s.is_empty() => return Ok(Some(json!(default))),
+      (Filter::Default(_), Some(value)) => return Ok(Some(value)),
+      (Filter::Json, Some(value)) => return Ok(Some(json!(value.to_string()))),
+      (_, None) => return Ok(None),
+      (_, Some(value)) => interpolator::stringify(value),
+    };
+
+    let filtered = match self {
+      Filter::UrlEncode => utf8_percent_encode(&value, URL_COMPONENT).to_string(),
+      Filter::Base64 => BASE64.encode(value),
+      Filter::Base64Decode => {
+        let decoded = BASE64.decode(value.trim()).map_err(|e| format!("Invalid base64 value: {e}"))?;
+
+        String::from_utf8(decoded).map_err(|e| format!("Invalid base64 value: {e}"))?
+      }
+      Filter::Upper => value.to_uppercase(),
+      Filter::Lower => value.to_lowercase(),
+      Filter::Trim => value.trim().to_string(),
+      Filter::Sha256 => hex::encode(Sha256::digest(value)),
+      Filter::Hmac(key) => {
+        let mut mac = Hmac::<Sha256>::new_from_slice(key.as_bytes()).unwrap();
+        mac.update(value.as_bytes());
+
+        hex::encode(mac.finalize().into_bytes())
+      }
+      Filter::Json | Filter::Default(_) => value,
+    };
+
+    Ok(Some(json!(filtered)))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn apply(source: &str, value: Option<Value>) -> Result<Option<Value>, String> {
+    Filter::parse(source)?.iter().try_fold(value, |value, filter| filter.apply(value))
+  }
+
+  #[test]
+  fn parses_filters() {
+    assert_eq!(Filter::parse("| upper |trim"), Ok(vec![Filter::Upper, Filter::Trim]));
+    assert_eq!(Filter::parse(r#"| default("a b") | hmac('secret')"#), Ok(vec![Filter::Default("a b".to_string()), Filter::Hmac("secret".to_string())]));
+    assert_eq!(Filter::parse(""), Ok(vec![]));
+  }
+
+  #[test]
+  fn invalid_filters() {
+    assert_eq!(Filter::parse("| reverse"), Err("Unknown 'reverse' filter!".to_string()));
+    assert_eq!(Filter::parse("| hmac"), Err("The 'hmac' filter needs an argument!".to_string()));
+  }
+
+  #[test]
+  fn applies_filters() {
+    assert_eq!(apply("| urlencode", Some(json!("drill & co/é"))), Ok(Some(json!("drill%20%26%20co%2F%C3%A9"))));
+    assert_eq!(apply("| base64", Some(json!("user:pass"))), Ok(Some(json!("dXNlcjpwYXNz"))));
+    assert_eq!(apply("| base64decode", Some(json!("dXNlcjpwYXNz"))), Ok(Some(json!("user:pass"))));
+    assert_eq!(apply("| json", Some(json!("say \"hi\""))), Ok(Some(json!("\"say \\\"hi\\\"\""))));
+    assert_eq!(apply("| json", Some(json!(12))), Ok(Some(json!("12"))));
+    assert_eq!(apply("| trim | upper", Some(json!("  drill "))), Ok(Some(json!("DRILL"))));
+    assert_eq!(apply("| lower", Some(json!("DRILL"))), Ok(Some(json!("drill"))));
+    assert_eq!(apply("| sha256", Some(json!("drill"))), Ok(Some(json!("b76419d9ad059fdfdc06637cb4db8ac93be25deb5bdf3cd2a7bbedaffe99d014"))));
+    assert_eq!(apply("| hmac('key')", Some(json!("The quick brown fox jumps over the lazy dog"))), Ok(Some(json!("f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8"))));
+    assert_eq!(apply("| upper", Some(json!(12))), Ok(Some(json!("12"))));
+  }
+
+  #[test]
+  fn applies_defaults() {
+    assert_eq!(apply("| default('x')", None), Ok(Some(json!("x"))));
+    assert_eq!(apply("| default('x')", Some(json!(""))), Ok(Some(json!("x"))));
+    assert_eq!(apply("| default('x')", Some(json!("y"))), Ok(Some(json!("y"))));
+    assert_eq!(apply("| upper | default('x')", None), Ok(Some(json!("x"))));
+    assert_eq!(apply("| upper", None), Ok(None));
+  }
+
+  #[test]
+  fn invalid_base64() {
+    assert!(apply("| base64decode", Some(json!("not base64!"))).is_err());
+  }
+}
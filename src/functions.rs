@@ -0,0 +1,151 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use chrono::format::{Item, StrftimeItems};
+use chrono::Utc;
+use lazy_static::lazy_static;
+use rand::distributions::Alphanumeric;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+lazy_static! {
+  // Sequences are shared by all the iterations
+  static ref SEQUENCES: Mutex<HashMap<String, u64>> = Mutex::new(HashMap::new());
+}
+
+// Evaluates a built-in function, like `{{ random_int(1, 100) }}`
+pub fn call(name: &str, arguments: &[Value]) -> Result<Value, String> {
+  match (name, arguments) {
+    ("uuid", []) => Ok(json!(Uuid::new_v4().to_string())),
+    ("random_int", [min, max]) => {
+      let (min, max) = (as_i64(min)?, as_i64(max)?);
+
+      if min > max {
+        return Err(format!("random_int needs a minimum lower than the maximum, but got {min} and {max}"));
+      }
+
+      Ok(json!(rand::thread_rng().gen_range(min..=max)))
+    }
+    ("random_string", [length]) => {
+      let length = usize::try_from(as_i64(length)?).map_err(|_| "random_string needs a positive length".to_string())?;
+
+      Ok(json!(rand::thread_rng().sample_iter(&Alphanumeric).take(length).map(char::from).collect::<String>()))
+    }
+    ("random_item", [list]) => {
+      let list = match list {
+        Value::Array(list) => list.clone(),
+        Value::String(s) => serde_json::from_str::<Vec<Value>>(s).map_err(|_| format!("random_item needs a list, but got {s:?}"))?,
+        _ => return Err(format!("random_item needs a list, but got {list}")),
+      };
+
+      list.choose(&mut rand::thread_rng()).cloned().ok_or_else(|| "random_item needs a non empty list".to_string())
+    }
+    ("now", []) => Ok(json!(Utc::now().to_rfc3339())),
+    ("now", [format]) => {
+      let format = as_str(format)?;
+
+      if StrftimeItems::new(&format).any(|item| item == Item::Error) {
+        return Err(format!("Invalid '{format}' time format"));
+      }
+
+      Ok(json!(Utc::now().format(&format).to_string()))
+    }
+    ("timestamp", []) => Ok(json!(Utc::now().timestamp())),
+    ("timestamp", [unit]) => match as_str(unit)?.as_str() {
+      "s" => Ok(json!(Utc::now().timestamp())),
+      "ms" => Ok(json!(Utc::now().timestamp_millis())),
+      "us" => Ok(json!(Utc::now().timestamp_micros())),
+      unit => Err(format!("Unknown '{unit}' timestamp unit! Use s, ms or us")),
+    },
+    ("sequence", []) => Ok(json!(next("default"))),
+    ("sequence", [name]) => Ok(json!(next(&as_str(name)?))),
+    ("uuid" | "random_int" | "random_string" | "random_item" | "now" | "timestamp" | "sequence", _) => Err(format!("Wrong number of arguments for the '{name}' function")),
+    _ => Err(format!("Unknown '{name}' function!")),
+  }
+}
+
+fn next(name: &str) -> u64 {
+  let mut sequences = SEQUENCES.lock().unwrap();
+  let value = sequences.entry(name.to_owned()).or_insert(0);
+
+  *value += 1;
+
+  *value
+}
+
+// Numbers may come from interpolated strings, like the iteration
+fn as_i64(value: &Value) -> Result<i64, String> {
+  match value {
+    Value::Number(n) => n.as_i64(),
+    Value::String(s) => s.trim().parse().ok(),
+    _ => None,
+  }
+  .ok_or_else(|| format!("Expected an integer, but got {value}"))
+}
+
+fn as_str(value: &Value) -> Result<String, String> {
+  match value {
+    Value::String(s) => Ok(s.to_owned()),
+    Value::Number(n) => Ok(n.to_string()),
+    _ => Err(format!("Expected a string, but got {value}")),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn generates_uuids() {
+    let uuid = call("uuid", &[]).unwrap();
+
+    assert_eq!(uuid.as_str().unwrap().len(), 36);
+    assert_ne!(uuid, call("uuid", &[]).unwrap());
+  }
+
+  #[test]
+  fn generates_random_values() {
+    for _ in 0..20 {
+      let value = call("random_int", &[json!(1), json!("3")]).unwrap().as_i64().unwrap();
+      assert!((1..=3).contains(&value));
+    }
+
+    assert_eq!(call("random_int", &[json!(5), json!(5)]), Ok(json!(5)));
+    assert!(call("random_int", &[json!(5), json!(1)]).is_err());
+
+    let string = call("random_string", &[json!(12)]).unwrap();
+    assert_eq!(string.as_str().unwrap().len(), 12);
+    assert!(string.as_str().unwrap().chars().all(|c| c.is_ascii_alphanumeric()));
+
+    assert_eq!(call("random_item", &[json!(["a"])]), Ok(json!("a")));
+    assert_eq!(call("random_item", &[json!("[1]")]), Ok(json!(1)));
+    assert!(call("random_item", &[json!([])]).is_err());
+  }
+
+  #[test]
+  fn generates_times() {
+    assert_eq!(call("now", &[json!("%Y")]).unwrap().as_str().unwrap().len(), 4);
+    assert!(call("now", &[]).unwrap().as_str().unwrap().contains('T'));
+    assert!(call("now", &[json!("%Q")]).is_err());
+
+    let seconds = call("timestamp", &[]).unwrap().as_i64().unwrap();
+    let millis = call("timestamp", &[json!("ms")]).unwrap().as_i64().unwrap();
+    assert!(millis / 1000 >= seconds);
+    assert!(call("timestamp", &[json!("days")]).is_err());
+  }
+
+  #[test]
+  fn generates_sequences() {
+    assert_eq!(call("sequence", &[json!("test_sequence")]), Ok(json!(1)));
+    assert_eq!(call("sequence", &[json!("test_sequence")]), Ok(json!(2)));
+    assert_eq!(call("sequence", &[json!("test_other_sequence")]), Ok(json!(1)));
+  }
+
+  #[test]
+  fn checks_functions() {
+    assert_eq!(call("uuid", &[json!(1)]), Err("Wrong number of arguments for the 'uuid' function".to_string()));
+    assert_eq!(call("unknown", &[]), Err("Unknown 'unknown' function!".to_string()));
+  }
+}
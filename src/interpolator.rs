@@ -2,18 +2,46 @@ use colored::*;
 use lazy_static::lazy_static;
 use regex::{Captures, Regex};
 use serde_json::json;
+use std::sync::RwLock;
 
 use crate::benchmark::Context;
+use crate::expression::Expression;
+use crate::faker;
+use crate::filters::{Filter, FILTERS_PATTERN};
+use crate::functions;
+use crate::shared;
 
 static INTERPOLATION_PREFIX: &str = "{{";
 static INTERPOLATION_SUFFIX: &str = "}}";
 
+// Function arguments can be strings, numbers or variables
+static ARGUMENT_PATTERN: &str = r#"(?:"([^"]*)"|'([^']*)'|(-?[0-9]+(?:\.[0-9]+)?)|([a-zA-Z]+[a-zA-Z\-\._\$0-9\[\]]*))"#;
+
 lazy_static! {
   pub static ref INTERPOLATION_REGEX: Regex = {
-    let regexp = format!("{}{}{}", regex::escape(INTERPOLATION_PREFIX), r" *([a-zA-Z]+[a-zA-Z\-\._\$0-9\[\]]*) *", regex::escape(INTERPOLATION_SUFFIX));
+    let arguments = format!(r"(?: *\((?P<arguments> *(?:{ARGUMENT_PATTERN}(?: *, *{ARGUMENT_PATTERN})*)? *)\))? *");
+    let regexp = format!("{}{}{}(?P<filters>{}){}", regex::escape(INTERPOLATION_PREFIX), r" *([a-zA-Z]+[a-zA-Z\-\._\$0-9\[\]]*)", arguments, FILTERS_PATTERN, regex::escape(INTERPOLATION_SUFFIX));
 
     Regex::new(regexp.as_str()).unwrap()
   };
+  static ref ARGUMENT_REGEX: Regex = Regex::new(ARGUMENT_PATTERN).unwrap();
+  // Text between `{% raw %}` and `{% endraw %}` is never interpolated
+  static ref RAW_START_REGEX: Regex = Regex::new(r"\{%\s*raw\s*%\}").unwrap();
+  static ref RAW_END_REGEX: Regex = Regex::new(r"\{%\s*endraw\s*%\}").unwrap();
+  // Variables known when the benchmark is loaded, like the ones given from the command line
+  static ref LOAD_CONTEXT: RwLock<Context> = RwLock::new(Context::new());
+}
+
+pub fn set_load_context(context: Context) {
+  *LOAD_CONTEXT.write().unwrap() = context;
+}
+
+// Resolves the properties read while loading the benchmark, like include paths. Only the variables
+// given from the command line or the environment profile, and environment variables are known
+pub fn resolve_on_load(text: &str) -> String {
+  let context = LOAD_CONTEXT.read().unwrap();
+
+  Interpolator::new(&context).resolve(text, true)
 }
 
 pub struct Interpolator<'a> {
@@ -28,27 +56,108 @@ impl<'a> Interpolator<'a> {
   }
 
   pub fn resolve(&self, url: &str, strict: bool) -> String {
+    scan(url)
+      .into_iter()
+      .map(|segment| match segment {
+        Segment::Text(text) | Segment::Raw(text) => text.to_string(),
+        // Any other interpolation is evaluated as an expression, like `{{ iteration * 10 + index }}`
+        Segment::Interpolation(whole, source) => match self.evaluate(whole, source, strict) {
+          Some(result) => stringify(self.unwrap_or_warn(result, strict)),
+          // Text which isn't a valid expression is left as it was, but may contain interpolations
+          None => self.resolve_interpolations(whole, strict),
+        },
+      })
+      .collect()
+  }
+
+  // Resolves a template made of a single interpolation, like `{{ user.ids }}`, keeping the JSON
+  // type of its value. Any other template is resolved into a string
+  pub fn resolve_value(&self, template: &str, strict: bool) -> serde_json::Value {
+    if let [Segment::Interpolation(whole, source)] = scan(template)[..] {
+      if let Some(result) = self.evaluate(whole, source, strict) {
+        return self.unwrap_or_warn(result, strict);
+      }
+    }
+
+    json!(self.resolve(template, strict))
+  }
+
+  fn resolve_interpolations(&self, url: &str, strict: bool) -> String {
     INTERPOLATION_REGEX
       .replace_all(url, |caps: &Captures| {
-        let capture = &caps[1];
-
-        if let Some(item) = self.resolve_context_interpolation(capture) {
-          return item;
-        }
+        let result = self.evaluate_interpolation(caps, strict);
 
-        if let Some(item) = self.resolve_environment_interpolation(capture) {
-          return item;
-        }
+        stringify(self.unwrap_or_warn(result, strict))
+      })
+      .to_string()
+  }
 
+  fn unwrap_or_warn(&self, result: Result<serde_json::Value, String>, strict: bool) -> serde_json::Value {
+    match result {
+      Ok(value) => value,
+      Err(error) => {
         if strict {
-          panic!("Unknown '{}' variable!", &capture);
+          panic!("{}", error);
         }
 
-        eprintln!("{} Unknown '{}' variable!", "WARNING!".yellow().bold(), &capture);
+        eprintln!("{} {}", "WARNING!".yellow().bold(), error);
 
-        "".to_string()
+        serde_json::Value::Null
+      }
+    }
+  }
+
+  // Evaluates the contents of `{{ ... }}`, or returns None if they aren't a valid expression
+  fn evaluate(&self, interpolation: &str, source: &str, strict: bool) -> Option<Result<serde_json::Value, String>> {
+    if let Some(caps) = INTERPOLATION_REGEX.captures(interpolation).filter(|caps| caps[0].len() == interpolation.len()) {
+      return Some(self.evaluate_interpolation(&caps, strict));
+    }
+
+    let expression = Expression::parse(source).ok()?;
+
+    Some(expression.evaluate(&|name| self.resolve_variable(name)).map_err(|error| format!("Invalid '{}' expression! {}", source.trim(), error)))
+  }
+
+  fn evaluate_interpolation(&self, caps: &Captures, strict: bool) -> Result<serde_json::Value, String> {
+    let capture = &caps[1];
+    let arguments = caps.name("arguments").map(|m| m.as_str());
+    let filters = match Filter::parse(caps.name("filters").map_or("", |m| m.as_str())) {
+      Ok(filters) => filters,
+      Err(error) if strict => return Err(format!("Invalid '{capture}' interpolation! {error}")),
+      // Relaxed interpolations keep the unfiltered value
+      Err(error) => {
+        eprintln!("{} Invalid '{capture}' interpolation! {error}", "WARNING!".yellow().bold());
+
+        Vec::new()
+      }
+    };
+
+    // Function calls and filtered values keep their JSON type until they're interpolated
+    let value = match arguments {
+      Some(arguments) => self.resolve_arguments(arguments).and_then(|arguments| functions::call(capture, &arguments)).map(Some),
+      None => Ok(self.resolve_variable(capture)),
+    };
+
+    match value.and_then(|value| filters.iter().try_fold(value, |value, filter| filter.apply(value))) {
+      Ok(Some(value)) => Ok(value),
+      Ok(None) => Err(format!("Unknown '{capture}' variable!")),
+      Err(error) => Err(format!("Invalid '{capture}' interpolation! {error}")),
+    }
+  }
+
+  fn resolve_arguments(&self, arguments: &str) -> Result<Vec<serde_json::Value>, String> {
+    ARGUMENT_REGEX
+      .captures_iter(arguments)
+      .map(|caps| {
+        if let Some(string) = caps.get(1).or_else(|| caps.get(2)) {
+          Ok(json!(string.as_str()))
+        } else if let Some(number) = caps.get(3) {
+          serde_json::from_str(number.as_str()).map_err(|e| e.to_string())
+        } else {
+          self.resolve_variable(&caps[4]).ok_or_else(|| format!("Unknown '{}' variable!", &caps[4]))
+        }
       })
-      .to_string()
+      .collect()
   }
 
   fn resolve_environment_interpolation(&self, value: &str) -> Option<String> {
@@ -58,22 +167,110 @@ impl<'a> Interpolator<'a> {
     }
   }
 
-  fn resolve_context_interpolation(&self, value: &str) -> Option<String> {
+  // Resolves a variable keeping its JSON type. Environment variables are always strings
+  pub fn resolve_variable(&self, value: &str) -> Option<serde_json::Value> {
+    self.resolve_context_value(value).or_else(|| self.resolve_environment_interpolation(value).map(|v| json!(v)))
+  }
+
+  fn resolve_context_value(&self, value: &str) -> Option<serde_json::Value> {
+    if let Some(path) = value.strip_prefix("shared.") {
+      return shared::get(path);
+    }
+
+    if let Some(name) = value.strip_prefix("fake.") {
+      return faker::fake(name).map(|value| json!(value));
+    }
+
     // convert "." and "[" to "/" and "]" to "" to look like a json pointer
     let val: String = format!("/{}", value.replace(['.', '['], "/").replace(']', ""));
 
     // force the context into a Value, and acess by pointer
-    if let Some(item) = json!(self.context).pointer(&val).to_owned() {
-      return Some(match item.to_owned() {
-        serde_json::Value::Null => "".to_owned(),
-        serde_json::Value::Bool(v) => v.to_string(),
-        serde_json::Value::Number(v) => v.to_string(),
-        serde_json::Value::String(v) => v,
-        serde_json::Value::Array(v) => serde_json::to_string(&v).unwrap(),
-        serde_json::Value::Object(v) => serde_json::to_string(&v).unwrap(),
-      });
+    json!(self.context).pointer(&val).cloned()
+  }
+}
+
+enum Segment<'a> {
+  Text(&'a str),
+  Raw(&'a str),
+  // The whole `{{ ... }}` interpolation, and its source
+  Interpolation(&'a str, &'a str),
+}
+
+// Splits a template into text, raw blocks and interpolations. Quoted strings in interpolations
+// can contain delimiters, so `{{ '{{' }}` is a literal `{{`
+fn scan(template: &str) -> Vec<Segment<'_>> {
+  let mut segments = Vec::new();
+  let mut rest = template;
+
+  loop {
+    let interpolation = rest.find(INTERPOLATION_PREFIX);
+    let raw = RAW_START_REGEX.find(rest).filter(|raw| interpolation.is_none_or(|start| raw.start() < start));
+
+    if let Some(raw) = raw {
+      let block = &rest[raw.end()..];
+      let end = RAW_END_REGEX.find(block);
+
+      segments.push(Segment::Text(&rest[..raw.start()]));
+      segments.push(Segment::Raw(&block[..end.map_or(block.len(), |end| end.start())]));
+      rest = &block[end.map_or(block.len(), |end| end.end())..];
+
+      continue;
+    }
+
+    let Some(start) = interpolation else {
+      segments.push(Segment::Text(rest));
+      segments.retain(|segment| !matches!(segment, Segment::Text("")));
+
+      return segments;
+    };
+
+    let source_start = start + INTERPOLATION_PREFIX.len();
+
+    match closing_delimiter(&rest[source_start..]).filter(|end| *end > 0) {
+      Some(end) => {
+        let end = source_start + end;
+
+        segments.push(Segment::Text(&rest[..start]));
+        segments.push(Segment::Interpolation(&rest[start..end + INTERPOLATION_SUFFIX.len()], &rest[source_start..end]));
+        rest = &rest[end + INTERPOLATION_SUFFIX.len()..];
+      }
+      // Unclosed or empty interpolations are text
+      None => {
+        let end = rest[source_start..].find(INTERPOLATION_SUFFIX).map_or(rest.len(), |end| source_start + end + INTERPOLATION_SUFFIX.len());
+
+        segments.push(Segment::Text(&rest[..end]));
+        rest = &rest[end..];
+      }
     }
-    None
+  }
+}
+
+// Finds the end of an interpolation, skipping delimiters in quoted strings. Unbalanced
+// quotes, like in `{{ it's }}`, are ignored
+fn closing_delimiter(source: &str) -> Option<usize> {
+  let mut quote: Option<char> = None;
+
+  for (index, c) in source.char_indices() {
+    match quote {
+      Some(q) if c == q => quote = None,
+      Some(_) => {}
+      None if c == '\'' || c == '"' => quote = Some(c),
+      None if source[index..].starts_with(INTERPOLATION_SUFFIX) => return Some(index),
+      None => {}
+    }
+  }
+
+  source.find(INTERPOLATION_SUFFIX)
+}
+
+pub fn stringify(value: serde_json::Value) -> String {
+  match value {
+    serde_json::Value::Null => "".to_owned(),
+    serde_json::Value::Bool(v) => v.to_string(),
+    serde_json::Value::Number(v) => v.to_string(),
+    serde_json::Value::String(v) => v,
+    serde_json::Value::Array(v) => serde_json::to_string(&v).unwrap(),
+    serde_json::Value::Object(v) => serde_json::to_string(&v).unwrap(),
   }
 }
 
@@ -122,6 +319,152 @@ mod tests {
     assert_eq!(interpolator.resolve("{{ ArrayNested[0].a[1].aaa[0].$aaaa }}", true), "$123".to_string());
   }
 
+  #[test]
+  fn interpolates_filters() {
+    let mut context: Context = Context::new();
+
+    context.insert(String::from("query"), json!("drill & co"));
+    context.insert(String::from("user"), json!({"name": " Drill "}));
+
+    let interpolator = Interpolator::new(&context);
+
+    assert_eq!(interpolator.resolve("/search?q={{ query | urlencode }}", true), "/search?q=drill%20%26%20co".to_string());
+    assert_eq!(interpolator.resolve("{{ user.name|trim|lower }}", true), "drill".to_string());
+    assert_eq!(interpolator.resolve("{\"q\": {{ query | json }}}", true), "{\"q\": \"drill & co\"}".to_string());
+    assert_eq!(interpolator.resolve("{{ missing | default(\"none\") }}", true), "none".to_string());
+    assert_eq!(interpolator.resolve("{{ missing | upper }}", false), "".to_string());
+  }
+
+  #[test]
+  fn interpolates_functions() {
+    let mut context: Context = Context::new();
+
+    context.insert(String::from("colors"), json!(["red"]));
+    context.insert(String::from("iteration"), json!("7"));
+
+    let interpolator = Interpolator::new(&context);
+
+    assert_eq!(interpolator.resolve("/{{ uuid() }}", true).len(), 37);
+    assert_eq!(interpolator.resolve("{{ random_int(iteration, 7) }}-{{ random_item( colors ) | upper }}", true), "7-RED".to_string());
+    assert_eq!(interpolator.resolve("{{ random_string(4) }}", true).len(), 4);
+    assert_eq!(interpolator.resolve("{{ sequence('test_interpolation') }} {{ sequence(\"test_interpolation\") }}", true), "1 2".to_string());
+    assert_eq!(interpolator.resolve("{{ unknown() }}", false), "".to_string());
+  }
+
+  #[test]
+  #[should_panic(expected = "Unknown 'missing' variable!")]
+  fn interpolates_functions_missing_variable() {
+    let context: Context = Context::new();
+
+    Interpolator::new(&context).resolve("{{ random_item(missing) }}", true);
+  }
+
+  #[test]
+  #[should_panic(expected = "Unknown 'missing' variable!")]
+  fn interpolates_filters_missing_variable() {
+    let context: Context = Context::new();
+
+    Interpolator::new(&context).resolve("{{ missing | upper }}", true);
+  }
+
+  #[test]
+  fn interpolates_expressions() {
+    let mut context: Context = Context::new();
+
+    context.insert(String::from("iteration"), json!(3));
+    context.insert(String::from("index"), json!(2));
+    context.insert(String::from("user"), json!({"name": "drill", "admin": false}));
+
+    let interpolator = Interpolator::new(&context);
+
+    assert_eq!(interpolator.resolve("/page/{{ iteration * 10 + index }}", true), "/page/32".to_string());
+    assert_eq!(interpolator.resolve("{{ 'user-' + user.name }}", true), "user-drill".to_string());
+    assert_eq!(interpolator.resolve("{{ user.admin ? 'admin' : 'guest' }}", true), "guest".to_string());
+    assert_eq!(interpolator.resolve("{{ iteration >= 3 and not user.admin }}", true), "true".to_string());
+    assert_eq!(interpolator.resolve("{{ random_int(1, 1) * iteration }}", true), "3".to_string());
+    assert_eq!(interpolator.resolve("{{ missing + 1 }}", false), "".to_string());
+    assert_eq!(interpolator.resolve("{{ 1 + }} {{{ iteration }}", true), "{{ 1 + }} {3".to_string());
+  }
+
+  #[test]
+  #[should_panic(expected = "Invalid 'iteration / 0' expression! Division by zero")]
+  fn interpolates_invalid_expressions() {
+    let mut context: Context = Context::new();
+
+    context.insert(String::from("iteration"), json!(3));
+
+    Interpolator::new(&context).resolve("{{ iteration / 0 }}", true);
+  }
+
+  #[test]
+  fn interpolates_fakes() {
+    let _lock = faker::TEST_LOCK.lock().unwrap();
+    let context: Context = Context::new();
+
+    let interpolator = Interpolator::new(&context);
+
+    assert!(interpolator.resolve("{{ fake.email }}", true).contains('@'));
+    assert_eq!(interpolator.resolve("{{ fake.unknown }}", false), "".to_string());
+  }
+
+  #[test]
+  fn interpolates_shared_variables() {
+    let context: Context = Context::new();
+
+    shared::set("test_interpolation", json!({"id": 7}));
+
+    let interpolator = Interpolator::new(&context);
+
+    assert_eq!(interpolator.resolve("/orders/{{ shared.test_interpolation.id }}", true), "/orders/7".to_string());
+  }
+
+  #[test]
+  fn interpolates_typed_values() {
+    let mut context: Context = Context::new();
+
+    context.insert(String::from("id"), json!(12));
+    context.insert(String::from("tags"), json!(["a", "b"]));
+
+    let interpolator = Interpolator::new(&context);
+
+    assert_eq!(interpolator.resolve_value("{{ id }}", true), json!(12));
+    assert_eq!(interpolator.resolve_value("{{ tags }}", true), json!(["a", "b"]));
+    assert_eq!(interpolator.resolve_value("{{ id + 1 }}", true), json!(13));
+    assert_eq!(interpolator.resolve_value("{{ id | default('x') }}", true), json!(12));
+    assert_eq!(interpolator.resolve_value("user-{{ id }}", true), json!("user-12"));
+    assert_eq!(interpolator.resolve_value("{{ id }}-{{ id }}", true), json!("12-12"));
+    assert_eq!(interpolator.resolve_value("plain", true), json!("plain"));
+  }
+
+  #[test]
+  fn interpolates_escaped_delimiters() {
+    let mut context: Context = Context::new();
+
+    context.insert(String::from("id"), json!(12));
+
+    let interpolator = Interpolator::new(&context);
+
+    assert_eq!(interpolator.resolve("{{ '{{' }} name {{ '}}' }}", true), "{{ name }}");
+    assert_eq!(interpolator.resolve(r#"{{ "{{ name }}" }} is {{ id }}"#, true), "{{ name }} is 12");
+    assert_eq!(interpolator.resolve("{{ name | default('}}') }}", true), "}}");
+    assert_eq!(interpolator.resolve("{{ it's }} {{ id }}", true), "{{ it's }} 12");
+    assert_eq!(interpolator.resolve("{{}} {{ id", true), "{{}} {{ id");
+  }
+
+  #[test]
+  fn interpolates_raw_blocks() {
+    let mut context: Context = Context::new();
+
+    context.insert(String::from("id"), json!(12));
+
+    let interpolator = Interpolator::new(&context);
+    let body = r#"{"query": "{% raw %}{ user(id: {{ id }}) { name } }{% endraw %}", "id": {{ id }}}"#;
+
+    assert_eq!(interpolator.resolve(body, true), r#"{"query": "{ user(id: {{ id }}) { name } }", "id": 12}"#);
+    assert_eq!(interpolator.resolve("{%raw%}{{ id }}", true), "{{ id }}");
+    assert_eq!(interpolator.resolve_value("{% raw %}{{ id }}{% endraw %}", true), json!("{{ id }}"));
+  }
+
   #[test]
   #[should_panic]
   fn interpolates_missing_variable() {
@@ -143,6 +486,28 @@ mod tests {
     assert_eq!(interpolated, "/users/");
   }
 
+  #[test]
+  fn interpolates_unknown_filters_relaxed() {
+    let mut context: Context = Context::new();
+
+    context.insert(String::from("name"), json!("Drill"));
+
+    let interpolator = Interpolator::new(&context);
+
+    assert_eq!(interpolator.resolve("/users/{{ name | reverse | upper }}", false), "/users/Drill");
+    assert_eq!(interpolator.resolve_value("{{ name | hmac }}", false), json!("Drill"));
+  }
+
+  #[test]
+  #[should_panic(expected = "Invalid 'name' interpolation! Unknown 'reverse' filter!")]
+  fn interpolates_unknown_filters() {
+    let mut context: Context = Context::new();
+
+    context.insert(String::from("name"), json!("Drill"));
+
+    Interpolator::new(&context).resolve("/users/{{ name | reverse }}", true);
+  }
+
   #[test]
   fn interpolates_numnamed_variables() {
     let mut context: Context = Context::new();
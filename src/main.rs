@@ -2,9 +2,19 @@ mod actions;
 mod benchmark;
 mod checker;
 mod config;
+mod environment;
 mod expandable;
+mod expectation;
+mod expression;
+mod extractor;
+mod faker;
+mod filters;
+mod functions;
 mod interpolator;
 mod reader;
+mod scenario;
+mod secrets;
+mod shared;
 mod tags;
 mod writer;
 
@@ -34,10 +44,23 @@ fn main() {
   let skip_tags_option = matches.value_of("skip-tags");
   let list_tags = matches.is_present("list-tags");
   let list_tasks = matches.is_present("list-tasks");
+  let var_options: Vec<&str> = matches.values_of("var").map_or_else(Vec::new, |values| values.collect());
+  let vars_file_option = matches.value_of("vars-file");
+  let env_file_option = matches.value_of("env-file");
+  let env_option = matches.value_of("env");
 
   #[cfg(windows)]
   let _ = control::set_virtual_terminal(true);
 
+  if let Some(env_file) = env_file_option {
+    environment::load_env_file(env_file);
+  }
+
+  let vars = config::read_vars(&var_options, vars_file_option);
+
+  // Benchmarks listed without running them are loaded with the command line variables only
+  interpolator::set_load_context(vars.clone());
+
   if list_tags {
     tags::list_benchmark_file_tags(benchmark_file);
     process::exit(0);
@@ -50,7 +73,7 @@ fn main() {
     process::exit(0);
   };
 
-  let benchmark_result = benchmark::execute(benchmark_file, report_path_option, relaxed_interpolations, no_check_certificate, quiet, nanosec, timeout, verbose, &tags);
+  let benchmark_result = benchmark::execute(benchmark_file, report_path_option, relaxed_interpolations, no_check_certificate, quiet, nanosec, timeout, verbose, &tags, vars, env_option);
   let list_reports = benchmark_result.reports;
   let duration = benchmark_result.duration;
 
@@ -79,6 +102,10 @@ fn app_args<'a>() -> clap::ArgMatches<'a> {
     .arg(Arg::with_name("timeout").short("o").long("timeout").help("Set timeout in seconds for all requests").takes_value(true))
     .arg(Arg::with_name("nanosec").short("n").long("nanosec").help("Shows statistics in nanoseconds").takes_value(false))
     .arg(Arg::with_name("verbose").short("v").long("verbose").help("Toggle verbose output").takes_value(false))
+    .arg(Arg::with_name("var").long("var").help("Sets a variable as name=value. Can be repeated").takes_value(true).multiple(true).number_of_values(1))
+    .arg(Arg::with_name("vars-file").long("vars-file").help("Sets a YAML file with variables").takes_value(true))
+    .arg(Arg::with_name("env-file").long("env-file").help("Sets a .env file with environment variables").takes_value(true))
+    .arg(Arg::with_name("env").short("e").long("env").help("Sets the environment profile to use").takes_value(true))
     .get_matches()
 }
 
@@ -86,6 +113,10 @@ struct DrillStats {
   total_requests: usize,
   successful_requests: usize,
   failed_requests: usize,
+  validation_failures: usize,
+  retried_attempts: usize,
+  sla_requests: usize,
+  sla_violations: usize,
   hist: Histogram<u64>,
 }
 
@@ -102,13 +133,20 @@ impl DrillStats {
   fn value_at_quantile(&self, quantile: f64) -> f64 {
     self.hist.value_at_quantile(quantile) as f64 / 1_000.0
   }
+  fn sla_violations_percentage(&self) -> f64 {
+    self.sla_violations as f64 * 100.0 / self.sla_requests as f64
+  }
 }
 
-fn compute_stats(sub_reports: &[Report]) -> DrillStats {
+fn compute_stats(reports: &[Report]) -> DrillStats {
+  // Only the final outcome of the retried requests is accounted
+  let retried_attempts = reports.iter().filter(|r| r.retried).count();
+  let sub_reports: Vec<&Report> = reports.iter().filter(|r| !r.retried).collect();
+
   let mut hist = Histogram::<u64>::new_with_bounds(1, 60 * 60 * 1000, 2).unwrap();
   let mut group_by_status = HashMap::new();
 
-  for req in sub_reports {
+  for req in sub_reports.iter() {
     group_by_status.entry(req.status / 100).or_insert_with(Vec::new).push(req);
   }
 
@@ -117,13 +155,20 @@ fn compute_stats(sub_reports: &[Report]) -> DrillStats {
   }
 
   let total_requests = sub_reports.len();
-  let successful_requests = group_by_status.entry(2).or_insert_with(Vec::new).len();
+  let successful_requests = group_by_status.entry(2).or_insert_with(Vec::new).iter().filter(|r| r.error.is_none()).count();
   let failed_requests = total_requests - successful_requests;
+  let validation_failures = sub_reports.iter().filter(|r| r.error.is_some()).count();
+  let sla_requests = sub_reports.iter().filter(|r| r.max_duration.is_some()).count();
+  let sla_violations = sub_reports.iter().filter(|r| r.exceeds_max_duration()).count();
 
   DrillStats {
     total_requests,
     successful_requests,
     failed_requests,
+    validation_failures,
+    retried_attempts,
+    sla_requests,
+    sla_violations,
     hist,
   }
 }
@@ -154,16 +199,25 @@ fn show_stats(list_reports: &[Vec<Report>], stats_option: bool, nanosec: bool, d
     println!("{:width$} {:width2$} {}", name.green(), "Total requests".yellow(), substats.total_requests.to_string().purple(), width = 25, width2 = 25);
     println!("{:width$} {:width2$} {}", name.green(), "Successful requests".yellow(), substats.successful_requests.to_string().purple(), width = 25, width2 = 25);
     println!("{:width$} {:width2$} {}", name.green(), "Failed requests".yellow(), substats.failed_requests.to_string().purple(), width = 25, width2 = 25);
+    if substats.validation_failures > 0 {
+      println!("{:width$} {:width2$} {}", name.green(), "Validation failures".yellow(), substats.validation_failures.to_string().purple(), width = 25, width2 = 25);
+    }
+    if substats.retried_attempts > 0 {
+      println!("{:width$} {:width2$} {}", name.green(), "Retried attempts".yellow(), substats.retried_attempts.to_string().purple(), width = 25, width2 = 25);
+    }
     println!("{:width$} {:width2$} {}", name.green(), "Median time per request".yellow(), format_time(substats.median_duration(), nanosec).purple(), width = 25, width2 = 25);
     println!("{:width$} {:width2$} {}", name.green(), "Average time per request".yellow(), format_time(substats.mean_duration(), nanosec).purple(), width = 25, width2 = 25);
     println!("{:width$} {:width2$} {}", name.green(), "Sample standard deviation".yellow(), format_time(substats.stdev_duration(), nanosec).purple(), width = 25, width2 = 25);
     println!("{:width$} {:width2$} {}", name.green(), "99.0'th percentile".yellow(), format_time(substats.value_at_quantile(0.99), nanosec).purple(), width = 25, width2 = 25);
     println!("{:width$} {:width2$} {}", name.green(), "99.5'th percentile".yellow(), format_time(substats.value_at_quantile(0.995), nanosec).purple(), width = 25, width2 = 25);
     println!("{:width$} {:width2$} {}", name.green(), "99.9'th percentile".yellow(), format_time(substats.value_at_quantile(0.999), nanosec).purple(), width = 25, width2 = 25);
+    if substats.sla_requests > 0 {
+      println!("{:width$} {:width2$} {} {}", name.green(), "SLA violations".yellow(), substats.sla_violations.to_string().purple(), format!("({:.2}%)", substats.sla_violations_percentage()).purple(), width = 25, width2 = 25);
+    }
   }
 
   // compute global stats
-  let allreports = list_reports.concat();
+  let allreports: Vec<Report> = list_reports.concat().into_iter().filter(|r| !r.aggregate).collect();
   let global_stats = compute_stats(&allreports);
   let requests_per_second = global_stats.total_requests as f64 / duration;
 
@@ -172,6 +226,9 @@ fn show_stats(list_reports: &[Vec<Report>], stats_option: bool, nanosec: bool, d
   println!("{:width2$} {}", "Total requests".yellow(), global_stats.total_requests.to_string().purple(), width2 = 25);
   println!("{:width2$} {}", "Successful requests".yellow(), global_stats.successful_requests.to_string().purple(), width2 = 25);
   println!("{:width2$} {}", "Failed requests".yellow(), global_stats.failed_requests.to_string().purple(), width2 = 25);
+  if global_stats.retried_attempts > 0 {
+    println!("{:width2$} {}", "Retried attempts".yellow(), global_stats.retried_attempts.to_string().purple(), width2 = 25);
+  }
   println!("{:width2$} {} {}", "Requests per second".yellow(), format!("{requests_per_second:.2}").purple(), "[#/sec]".purple(), width2 = 25);
   println!("{:width2$} {}", "Median time per request".yellow(), format_time(global_stats.median_duration(), nanosec).purple(), width2 = 25);
   println!("{:width2$} {}", "Average time per request".yellow(), format_time(global_stats.mean_duration(), nanosec).purple(), width2 = 25);
@@ -0,0 +1,162 @@
+use rand::distributions::{Distribution, WeightedIndex};
+use serde_yaml::Value;
+
+use crate::benchmark::Benchmark;
+use crate::expandable::include;
+use crate::reader;
+use crate::tags::Tags;
+
+const NWEIGHT: u64 = 1;
+
+pub struct Scenario {
+  pub name: Option<String>,
+  pub benchmark: Benchmark,
+}
+
+pub struct Scenarios {
+  pub scenarios: Vec<Scenario>,
+  weights: Option<WeightedIndex<u64>>,
+}
+
+impl Scenarios {
+  pub fn load(benchmark_path: &str, tags: &Tags) -> Scenarios {
+    let docs = reader::read_file_as_yml(benchmark_path);
+    let doc = &docs[0];
+
+    let mut scenarios = Vec::new();
+    let mut weights = Vec::new();
+
+    match doc.get("scenarios") {
+      Some(items) => {
+        for item in items.as_sequence().expect("`scenarios` needs to be a sequence") {
+          let name = item.get("name").and_then(|v| v.as_str()).expect("Scenarios need a `name`");
+          let weight = match item.get("weight") {
+            Some(weight) => weight.as_u64().expect("`weight` needs to be a positive number"),
+            None => NWEIGHT,
+          };
+
+          let mut benchmark: Benchmark = Benchmark::new();
+          include::expand_items(benchmark_path, reader::read_yaml_doc_accessor(item, Some("plan")), &mut benchmark, tags);
+
+          // Scenarios without items for the current tags never run
+          if !benchmark.is_empty() {
+            scenarios.push(Scenario {
+              name: Some(name.to_string()),
+              benchmark,
+            });
+            weights.push(weight);
+          }
+        }
+      }
+      None => {
+        let mut benchmark: Benchmark = Benchmark::new();
+        include::expand_items(benchmark_path, reader::read_yaml_doc_accessor(doc, Some("plan")), &mut benchmark, tags);
+
+        return Scenarios::single(benchmark);
+      }
+    }
+
+    // Even a single scenario is picked by weight, so a scenario with a zero weight never runs
+    let weights = if scenarios.is_empty() {
+      None
+    } else {
+      Some(WeightedIndex::new(weights).expect("Scenario weights can't all be zero"))
+    };
+
+    Scenarios {
+      scenarios,
+      weights,
+    }
+  }
+
+  // Benchmarks with a single plan, run by every iteration
+  pub fn single(benchmark: Benchmark) -> Scenarios {
+    Scenarios {
+      scenarios: vec![Scenario {
+        name: None,
+        benchmark,
+      }],
+      weights: None,
+    }
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.scenarios.iter().all(|scenario| scenario.benchmark.is_empty())
+  }
+
+  pub fn pick(&self) -> &Scenario {
+    match &self.weights {
+      Some(weights) => &self.scenarios[weights.sample(&mut rand::thread_rng())],
+      None => &self.scenarios[0],
+    }
+  }
+}
+
+// Items of the benchmark plan, or of all its scenarios
+pub fn plan_items(doc: &Value) -> Vec<&Value> {
+  match doc.get("scenarios").and_then(|v| v.as_sequence()) {
+    Some(scenarios) => scenarios.iter().flat_map(|scenario| reader::read_yaml_doc_accessor(scenario, Some("plan"))).collect(),
+    None => reader::read_yaml_doc_accessor(doc, Some("plan")).iter().collect(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  use std::io::Write;
+
+  fn load(text: &str, tags: &Tags) -> Scenarios {
+    let mut file = tempfile::NamedTempFile::new().unwrap();
+    file.write_all(text.as_bytes()).unwrap();
+
+    Scenarios::load(file.path().to_str().unwrap(), tags)
+  }
+
+  #[test]
+  fn loads_plan() {
+    let scenarios = load("---\nplan:\n  - name: foo\n    request:\n      url: /foo", &Tags::new(None, None));
+
+    assert_eq!(scenarios.scenarios.len(), 1);
+    assert!(scenarios.pick().name.is_none());
+    assert_eq!(scenarios.pick().benchmark.len(), 1);
+  }
+
+  #[test]
+  fn loads_scenarios() {
+    let text = "---\nscenarios:\n  - name: browse\n    weight: 3\n    plan:\n      - name: foo\n        request:\n          url: /foo\n  - name: checkout\n    weight: 0\n    plan:\n      - name: bar\n        request:\n          url: /bar\n        tags:\n          - checkout";
+    let scenarios = load(text, &Tags::new(None, None));
+
+    assert_eq!(scenarios.scenarios.len(), 2);
+
+    for _ in 0..10 {
+      assert_eq!(scenarios.pick().name.as_deref(), Some("browse"));
+    }
+
+    let scenarios = load(&text.replace("weight: 0", "weight: 1"), &Tags::new(Some("checkout"), None));
+
+    assert_eq!(scenarios.scenarios.len(), 1);
+    assert_eq!(scenarios.pick().name.as_deref(), Some("checkout"));
+  }
+
+  #[test]
+  #[should_panic(expected = "Scenario weights can't all be zero")]
+  fn zero_weight_scenarios() {
+    let text = "---\nscenarios:\n  - name: browse\n    weight: 3\n    plan:\n      - name: foo\n        request:\n          url: /foo\n  - name: checkout\n    weight: 0\n    plan:\n      - name: bar\n        request:\n          url: /bar\n        tags:\n          - checkout";
+
+    load(text, &Tags::new(Some("checkout"), None));
+  }
+
+  #[test]
+  fn lists_plan_items() {
+    let docs = crate::reader::read_file_as_yml_from_str("---\nscenarios:\n  - name: browse\n    plan:\n      - name: foo\n      - name: bar\n  - name: checkout\n    plan:\n      - name: baz");
+
+    assert_eq!(plan_items(&docs[0]).len(), 3);
+  }
+
+  #[test]
+  #[should_panic]
+  fn invalid_weight() {
+    load("---\nscenarios:\n  - name: browse\n    weight: heavy\n    plan:\n      - name: foo\n        request:\n          url: /foo", &Tags::new(None, None));
+  }
+}
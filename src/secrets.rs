@@ -0,0 +1,139 @@
+use std::fs;
+use std::path::Path;
+use std::sync::RwLock;
+
+use lazy_static::lazy_static;
+use percent_encoding::utf8_percent_encode;
+use serde_json::json;
+use serde_yaml::Value;
+
+use crate::benchmark::Context;
+use crate::filters::URL_COMPONENT;
+use crate::interpolator;
+
+const MASK: &str = "********";
+
+lazy_static! {
+  // Values of the secret variables, which are masked in every log, report and error
+  static ref SECRETS: RwLock<Vec<String>> = RwLock::new(Vec::new());
+}
+
+// Reads the `secrets` section of the benchmark. Each secret comes from an environment
+// variable, a file relative to the benchmark or a plain value, unless it's overridden
+// from the command line
+pub fn load(doc: &Value, benchmark_path: &str, overrides: &Context) -> Context {
+  let mut secrets = Context::new();
+
+  if let Some(mapping) = doc.get("secrets").and_then(|v| v.as_mapping()) {
+    for (name, source) in mapping.iter() {
+      let name = name.as_str().unwrap_or_else(|| panic!("Secret names need to be strings"));
+      let value = match overrides.get(name) {
+        Some(value) => interpolator::stringify(value.clone()),
+        None => read(name, source, benchmark_path),
+      };
+
+      register(&value);
+      secrets.insert(name.to_string(), json!(value));
+    }
+  }
+
+  secrets
+}
+
+fn read(name: &str, source: &Value, benchmark_path: &str) -> String {
+  if let Some(value) = source.as_str() {
+    value.to_owned()
+  } else if let Some(value) = source.get("value").and_then(|v| v.as_str()) {
+    value.to_owned()
+  } else if let Some(var) = source.get("env").and_then(|v| v.as_str()) {
+    std::env::var(var).unwrap_or_else(|_| panic!("Unknown '{var}' environment variable for the '{name}' secret!"))
+  } else if let Some(file) = source.get("file").and_then(|v| v.as_str()) {
+    let path = Path::new(benchmark_path).with_file_name(file);
+    let content = fs::read_to_string(&path).unwrap_or_else(|_| panic!("Unable to read the file {} for the '{name}' secret!", path.display()));
+
+    content.trim_end_matches(['\r', '\n']).to_owned()
+  } else {
+    panic!("The '{name}' secret needs a value, env or file");
+  }
+}
+
+// Registers a secret value, and its url encoded form for the ones sent in urls
+pub fn register(value: &str) {
+  if value.is_empty() {
+    return;
+  }
+
+  let mut secrets = SECRETS.write().unwrap();
+
+  for value in [value.to_owned(), utf8_percent_encode(value, URL_COMPONENT).to_string()] {
+    if !secrets.contains(&value) {
+      secrets.push(value);
+    }
+  }
+
+  // Longer secrets first, so secrets containing others are fully masked
+  secrets.sort_by_key(|secret| std::cmp::Reverse(secret.len()));
+}
+
+pub fn redact(text: &str) -> String {
+  SECRETS.read().unwrap().iter().fold(text.to_owned(), |text, secret| text.replace(secret.as_str(), MASK))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::io::Write;
+  use tempfile::NamedTempFile;
+
+  #[test]
+  fn loads_secrets() {
+    let mut file = NamedTempFile::new().unwrap();
+    file.write_all(b"file-secret-value\n").unwrap();
+
+    std::env::set_var("DRILL_TEST_SECRET", "env-secret-value");
+
+    let text = format!("---\nsecrets:\n  plain: plain-secret-value\n  token:\n    env: DRILL_TEST_SECRET\n  key:\n    file: {}\n", file.path().file_name().unwrap().to_str().unwrap());
+    let doc = &crate::reader::read_file_as_yml_from_str(&text)[0];
+    let secrets = load(doc, file.path().to_str().unwrap(), &Context::new());
+
+    assert_eq!(secrets.get("plain"), Some(&json!("plain-secret-value")));
+    assert_eq!(secrets.get("token"), Some(&json!("env-secret-value")));
+    assert_eq!(secrets.get("key"), Some(&json!("file-secret-value")));
+    assert_eq!(redact("Bearer env-secret-value, file-secret-value"), "Bearer ********, ********");
+  }
+
+  #[test]
+  fn redacts_longer_secrets_first() {
+    register("redacted-abc");
+    register("redacted-abc-def");
+
+    assert_eq!(redact("key=redacted-abc-def&other=redacted-abc"), "key=********&other=********");
+  }
+
+  #[test]
+  fn redacts_url_encoded_secrets() {
+    register("redacted+a/b=");
+
+    assert_eq!(redact("/login?token=redacted%2Ba%2Fb%3D"), "/login?token=********");
+  }
+
+  #[test]
+  #[should_panic(expected = "Unknown 'DRILL_MISSING_SECRET' environment variable for the 'token' secret!")]
+  fn missing_secret() {
+    let doc = &crate::reader::read_file_as_yml_from_str("---\nsecrets:\n  token:\n    env: DRILL_MISSING_SECRET\n")[0];
+
+    load(doc, "benchmark.yml", &Context::new());
+  }
+
+  #[test]
+  fn overrides_secrets() {
+    let doc = &crate::reader::read_file_as_yml_from_str("---\nsecrets:\n  token:\n    env: DRILL_OVERRIDDEN_SECRET\n")[0];
+    let mut overrides = Context::new();
+    overrides.insert("token".to_string(), json!("cli-secret-value"));
+
+    let secrets = load(doc, "benchmark.yml", &overrides);
+
+    assert_eq!(secrets.get("token"), Some(&json!("cli-secret-value")));
+    assert_eq!(redact("Bearer cli-secret-value"), "Bearer ********");
+  }
+}
@@ -0,0 +1,90 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+use lazy_static::lazy_static;
+use serde_json::{json, Map, Value};
+
+// Variables, counters and queues shared by all the iterations
+#[derive(Default)]
+struct Store {
+  vars: Map<String, Value>,
+  queues: HashMap<String, VecDeque<Value>>,
+}
+
+lazy_static! {
+  static ref STORE: Mutex<Store> = Mutex::new(Store::default());
+}
+
+// Looks up a variable, or the items of a queue, like context interpolations do
+pub fn get(path: &str) -> Option<Value> {
+  let store = STORE.lock().unwrap();
+  let pointer = format!("/{}", path.replace(['.', '['], "/").replace(']', ""));
+
+  if let Some(value) = json!(store.vars).pointer(&pointer) {
+    return Some(value.clone());
+  }
+
+  let queues: Map<String, Value> = store.queues.iter().map(|(key, queue)| (key.to_owned(), json!(queue))).collect();
+
+  json!(queues).pointer(&pointer).cloned()
+}
+
+pub fn set(key: &str, value: Value) {
+  STORE.lock().unwrap().vars.insert(key.to_owned(), value);
+}
+
+pub fn incr(key: &str, by: i64) -> i64 {
+  let mut store = STORE.lock().unwrap();
+  let counter = store.vars.get(key).and_then(|v| v.as_i64()).unwrap_or(0) + by;
+
+  store.vars.insert(key.to_owned(), json!(counter));
+
+  counter
+}
+
+pub fn push(key: &str, value: Value) -> usize {
+  let mut store = STORE.lock().unwrap();
+  let queue = store.queues.entry(key.to_owned()).or_default();
+
+  queue.push_back(value);
+
+  queue.len()
+}
+
+pub fn pop(key: &str) -> Option<Value> {
+  STORE.lock().unwrap().queues.get_mut(key).and_then(|queue| queue.pop_front())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn sets_variables() {
+    set("test_set", json!({"id": 1}));
+
+    assert_eq!(get("test_set"), Some(json!({"id": 1})));
+    assert_eq!(get("test_set.id"), Some(json!(1)));
+    assert_eq!(get("test_unknown"), None);
+  }
+
+  #[test]
+  fn increments_counters() {
+    assert_eq!(incr("test_counter", 1), 1);
+    assert_eq!(incr("test_counter", 5), 6);
+    assert_eq!(get("test_counter"), Some(json!(6)));
+  }
+
+  #[test]
+  fn pushes_and_pops_queues() {
+    assert_eq!(push("test_queue", json!("a")), 1);
+    assert_eq!(push("test_queue", json!("b")), 2);
+    assert_eq!(get("test_queue"), Some(json!(["a", "b"])));
+    assert_eq!(get("test_queue[1]"), Some(json!("b")));
+
+    assert_eq!(pop("test_queue"), Some(json!("a")));
+    assert_eq!(pop("test_queue"), Some(json!("b")));
+    assert_eq!(pop("test_queue"), None);
+    assert_eq!(pop("test_unknown_queue"), None);
+  }
+}
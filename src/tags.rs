@@ -1,4 +1,5 @@
 use crate::reader;
+use crate::scenario;
 use colored::*;
 use serde_yaml::Value;
 use std::collections::HashSet;
@@ -58,7 +59,7 @@ impl<'a> Tags<'a> {
 
 pub fn list_benchmark_file_tasks(benchmark_file: &str, tags: &Tags) {
   let docs = reader::read_file_as_yml(benchmark_file);
-  let items = reader::read_yaml_doc_accessor(&docs[0], Some("plan"));
+  let items = scenario::plan_items(&docs[0]);
 
   println!();
 
@@ -88,7 +89,7 @@ pub fn list_benchmark_file_tasks(benchmark_file: &str, tags: &Tags) {
 
 pub fn list_benchmark_file_tags(benchmark_file: &str) {
   let docs = reader::read_file_as_yml(benchmark_file);
-  let items = reader::read_yaml_doc_accessor(&docs[0], Some("plan"));
+  let items = scenario::plan_items(&docs[0]);
 
   println!();
 